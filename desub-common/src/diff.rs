@@ -0,0 +1,354 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Compare the metadata of two runtime versions, to find out what changed at a runtime upgrade.
+//!
+//! Both current (V14+) and legacy metadata can be described by a [`MetadataShape`], which captures
+//! the parts of the metadata that affect decoding. Two shapes can then be compared with [`diff`],
+//! which produces a serialisable [`MetadataDiff`].
+//!
+//! Legacy metadata only names the types it refers to, and those names don't line up with the types in a
+//! V14+ type registry. When one version is legacy and the other isn't, pallets, indexes, hashers and the
+//! number and names of fields are still compared, but the types themselves can't be. Calls, events, errors
+//! and storage entries whose types couldn't be compared are listed as `not_comparable` rather than being
+//! assumed to be unchanged.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A version independent outline of the parts of some metadata which affect decoding.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct MetadataShape {
+	/// Each pallet, in index order.
+	pub pallets: Vec<PalletShape>,
+}
+
+/// The calls, events, errors and storage entries of a single pallet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PalletShape {
+	pub name: String,
+	pub index: u8,
+	pub calls: Vec<VariantShape>,
+	pub events: Vec<VariantShape>,
+	pub errors: Vec<VariantShape>,
+	pub storage: Vec<StorageShape>,
+}
+
+/// A call, event or error, along with the type of each of its fields.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct VariantShape {
+	pub name: String,
+	pub index: u8,
+	pub fields: Vec<TypeShape>,
+}
+
+/// A storage entry, along with the hashers and types of its keys and the type of its value.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StorageShape {
+	pub name: String,
+	pub hashers: Vec<String>,
+	pub keys: Vec<TypeShape>,
+	pub value: TypeShape,
+}
+
+/// A type, as found in a field or storage entry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TypeShape {
+	/// The name of the field, if it has one.
+	pub name: Option<String>,
+	/// A human readable signature of the type, like `Vec<(AccountId32, Compact<u128>)>`.
+	pub ty: String,
+	/// An opaque description of the structure of the type. Types with the same structure are
+	/// encoded identically, regardless of what they are called or where they live in a type registry.
+	/// This is `None` for legacy metadata, which only names its types.
+	pub structure: Option<String>,
+}
+
+impl TypeShape {
+	/// Compare the names and structure of two types. Legacy types are compared by their [`TypeShape::ty`]
+	/// instead, and are [`Comparison::NotComparable`] with V14+ types unless their field names differ.
+	pub fn compare(&self, other: &TypeShape) -> Comparison {
+		let same = match (&self.structure, &other.structure) {
+			(Some(a), Some(b)) => self.name == other.name && a == b,
+			(None, None) => self.name == other.name && self.ty == other.ty,
+			// Legacy event fields have no names, so only compare names that are on both sides:
+			_ if self.name.is_some() && other.name.is_some() && self.name != other.name => false,
+			_ => return Comparison::NotComparable,
+		};
+		if same {
+			Comparison::Same
+		} else {
+			Comparison::Changed
+		}
+	}
+}
+
+/// The result of comparing two [`TypeShape`]s, or lists of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+	Same,
+	Changed,
+	/// Nothing that could be compared has changed, but one side is from legacy metadata and the
+	/// other isn't, so the types themselves couldn't be compared.
+	NotComparable,
+}
+
+/// The differences between two [`MetadataShape`]s, as returned from [`diff`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct MetadataDiff {
+	/// Pallets that only exist in the newer metadata.
+	pub pallets_added: Vec<String>,
+	/// Pallets that only exist in the older metadata.
+	pub pallets_removed: Vec<String>,
+	/// Pallets whose name changed, but which kept the same index.
+	pub pallets_renamed: Vec<Renamed>,
+	/// Pallets present in both versions whose contents or index changed.
+	pub pallets_changed: Vec<PalletDiff>,
+}
+
+impl MetadataDiff {
+	/// Is there no difference between the two versions?
+	pub fn is_empty(&self) -> bool {
+		self.pallets_added.is_empty()
+			&& self.pallets_removed.is_empty()
+			&& self.pallets_renamed.is_empty()
+			&& self.pallets_changed.is_empty()
+	}
+}
+
+/// A pallet which was renamed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Renamed {
+	pub from: String,
+	pub to: String,
+}
+
+/// A value which changed between the two versions.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Change<T> {
+	pub from: T,
+	pub to: T,
+}
+
+/// The differences in a single pallet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PalletDiff {
+	/// The name of the pallet in the newer metadata.
+	pub name: String,
+	/// Set if the index of the pallet changed.
+	pub index: Option<Change<u8>>,
+	pub calls: VariantsDiff,
+	pub events: VariantsDiff,
+	pub errors: VariantsDiff,
+	pub storage: StorageDiff,
+}
+
+impl PalletDiff {
+	/// Is there no difference in this pallet?
+	pub fn is_empty(&self) -> bool {
+		self.index.is_none()
+			&& self.calls.is_empty()
+			&& self.events.is_empty()
+			&& self.errors.is_empty()
+			&& self.storage.is_empty()
+	}
+}
+
+/// The differences in the calls, events or errors of a pallet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct VariantsDiff {
+	pub added: Vec<String>,
+	pub removed: Vec<String>,
+	/// Variants whose index changed, and so are encoded differently.
+	pub index_shifts: Vec<IndexShift>,
+	/// Variants whose fields changed in name, number or type structure.
+	pub field_changes: Vec<FieldsChange>,
+	/// Variants whose fields have the same names and number, but whose types couldn't be compared.
+	pub not_comparable: Vec<String>,
+}
+
+impl VariantsDiff {
+	/// Are the variants unchanged?
+	pub fn is_empty(&self) -> bool {
+		self.added.is_empty()
+			&& self.removed.is_empty()
+			&& self.index_shifts.is_empty()
+			&& self.field_changes.is_empty()
+			&& self.not_comparable.is_empty()
+	}
+}
+
+/// A call, event or error whose index changed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IndexShift {
+	pub name: String,
+	pub from: u8,
+	pub to: u8,
+}
+
+/// A call, event or error whose fields changed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FieldsChange {
+	pub name: String,
+	pub from: Vec<TypeShape>,
+	pub to: Vec<TypeShape>,
+}
+
+/// The differences in the storage entries of a pallet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct StorageDiff {
+	pub added: Vec<String>,
+	pub removed: Vec<String>,
+	pub changed: Vec<StorageChange>,
+	/// Entries whose key or value types couldn't be compared.
+	pub not_comparable: Vec<String>,
+}
+
+impl StorageDiff {
+	/// Are the storage entries unchanged?
+	pub fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty() && self.not_comparable.is_empty()
+	}
+}
+
+/// A storage entry whose hashers, keys or value changed. Only the parts that changed are set.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StorageChange {
+	pub name: String,
+	pub hashers: Option<Change<Vec<String>>>,
+	pub keys: Option<Change<Vec<TypeShape>>>,
+	pub value: Option<Change<TypeShape>>,
+}
+
+/// Compare an older and a newer [`MetadataShape`]. Pallets are matched up by name, and a removed
+/// pallet and an added pallet which share an index are considered to be a renamed pallet.
+pub fn diff(old: &MetadataShape, new: &MetadataShape) -> MetadataDiff {
+	let old_by_name: HashMap<&str, &PalletShape> = old.pallets.iter().map(|p| (p.name.as_str(), p)).collect();
+	let new_by_name: HashMap<&str, &PalletShape> = new.pallets.iter().map(|p| (p.name.as_str(), p)).collect();
+
+	let mut removed: Vec<&PalletShape> =
+		old.pallets.iter().filter(|p| !new_by_name.contains_key(p.name.as_str())).collect();
+	let mut report = MetadataDiff::default();
+	let mut pairs = Vec::new();
+
+	for new_pallet in &new.pallets {
+		if let Some(old_pallet) = old_by_name.get(new_pallet.name.as_str()) {
+			pairs.push((*old_pallet, new_pallet));
+		} else if let Some(pos) = removed.iter().position(|p| p.index == new_pallet.index) {
+			let old_pallet = removed.remove(pos);
+			report.pallets_renamed.push(Renamed { from: old_pallet.name.clone(), to: new_pallet.name.clone() });
+			pairs.push((old_pallet, new_pallet));
+		} else {
+			report.pallets_added.push(new_pallet.name.clone());
+		}
+	}
+	report.pallets_removed = removed.into_iter().map(|p| p.name.clone()).collect();
+	report.pallets_changed = pairs
+		.into_iter()
+		.map(|(old_pallet, new_pallet)| diff_pallet(old_pallet, new_pallet))
+		.filter(|pallet_diff| !pallet_diff.is_empty())
+		.collect();
+
+	report
+}
+
+fn diff_pallet(old: &PalletShape, new: &PalletShape) -> PalletDiff {
+	PalletDiff {
+		name: new.name.clone(),
+		index: (old.index != new.index).then_some(Change { from: old.index, to: new.index }),
+		calls: diff_variants(&old.calls, &new.calls),
+		events: diff_variants(&old.events, &new.events),
+		errors: diff_variants(&old.errors, &new.errors),
+		storage: diff_storage(&old.storage, &new.storage),
+	}
+}
+
+fn diff_variants(old: &[VariantShape], new: &[VariantShape]) -> VariantsDiff {
+	let mut report = VariantsDiff {
+		removed: old.iter().filter(|o| !new.iter().any(|n| n.name == o.name)).map(|o| o.name.clone()).collect(),
+		..Default::default()
+	};
+
+	for new_variant in new {
+		let Some(old_variant) = old.iter().find(|o| o.name == new_variant.name) else {
+			report.added.push(new_variant.name.clone());
+			continue;
+		};
+		if old_variant.index != new_variant.index {
+			report.index_shifts.push(IndexShift {
+				name: new_variant.name.clone(),
+				from: old_variant.index,
+				to: new_variant.index,
+			});
+		}
+		match compare_all(&old_variant.fields, &new_variant.fields) {
+			Comparison::Same => {}
+			Comparison::Changed => report.field_changes.push(FieldsChange {
+				name: new_variant.name.clone(),
+				from: old_variant.fields.clone(),
+				to: new_variant.fields.clone(),
+			}),
+			Comparison::NotComparable => report.not_comparable.push(new_variant.name.clone()),
+		}
+	}
+
+	report
+}
+
+fn diff_storage(old: &[StorageShape], new: &[StorageShape]) -> StorageDiff {
+	let mut report = StorageDiff {
+		removed: old.iter().filter(|o| !new.iter().any(|n| n.name == o.name)).map(|o| o.name.clone()).collect(),
+		..Default::default()
+	};
+
+	for new_entry in new {
+		let Some(old_entry) = old.iter().find(|o| o.name == new_entry.name) else {
+			report.added.push(new_entry.name.clone());
+			continue;
+		};
+		let keys = compare_all(&old_entry.keys, &new_entry.keys);
+		let value = old_entry.value.compare(&new_entry.value);
+		let change = StorageChange {
+			name: new_entry.name.clone(),
+			hashers: (old_entry.hashers != new_entry.hashers)
+				.then(|| Change { from: old_entry.hashers.clone(), to: new_entry.hashers.clone() }),
+			keys: (keys == Comparison::Changed)
+				.then(|| Change { from: old_entry.keys.clone(), to: new_entry.keys.clone() }),
+			value: (value == Comparison::Changed)
+				.then(|| Change { from: old_entry.value.clone(), to: new_entry.value.clone() }),
+		};
+		if keys == Comparison::NotComparable || value == Comparison::NotComparable {
+			report.not_comparable.push(new_entry.name.clone());
+		}
+		if change.hashers.is_some() || change.keys.is_some() || change.value.is_some() {
+			report.changed.push(change);
+		}
+	}
+
+	report
+}
+
+// Any change to a list of types is reported, even if some of the other types couldn't be compared.
+fn compare_all(a: &[TypeShape], b: &[TypeShape]) -> Comparison {
+	if a.len() != b.len() {
+		return Comparison::Changed;
+	}
+	a.iter().zip(b).map(|(a, b)| a.compare(b)).fold(Comparison::Same, |acc, c| match (acc, c) {
+		(Comparison::Changed, _) | (_, Comparison::Changed) => Comparison::Changed,
+		(Comparison::NotComparable, _) | (_, Comparison::NotComparable) => Comparison::NotComparable,
+		_ => Comparison::Same,
+	})
+}
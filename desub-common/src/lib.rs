@@ -17,6 +17,8 @@
 //! Common types between legacy and current desub versions.
 
 #![forbid(unsafe_code)]
+pub mod diff;
+
 use serde::{Deserialize, Serialize};
 use sp_core::crypto::AccountId32;
 #[deny(unused)]
@@ -0,0 +1,383 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+	decode_address, decode_signature_only, decode_unwrapped_extrinsic, decode_value_by_id, extrinsic_version_and_kind,
+	DecodeError, Extrinsic, ExtrinsicKind,
+};
+use crate::metadata::Metadata;
+use crate::TypeId;
+use parity_scale_codec::{Compact, Decode};
+use scale_info::{form::PortableForm, Field, TypeDef, TypeDefPrimitive};
+use std::fmt::Write;
+use std::ops::Range;
+
+/// The maximum number of bytes of hex we'll print on a single line of a hex dump.
+const HEX_DUMP_MAX_BYTES: usize = 16;
+
+/// The longest sequence of zero sized values (like `Vec<()>`) that we'll decode. Such values take up no
+/// bytes, so unlike other sequences their length can't be checked against the number of bytes left.
+const MAX_ZERO_SIZED_SEQUENCE_LEN: u32 = 1024;
+
+/// A description of some range of bytes that were decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+	/// The range of bytes that this annotation refers to.
+	pub range: Range<usize>,
+	/// A dot separated path describing what the bytes represent, like `call.args.dest`.
+	pub path: String,
+	/// How deeply nested this annotation is. Annotations that are nested inside others
+	/// have ranges within their parent's range.
+	pub depth: usize,
+	/// A human readable representation of the decoded value. Annotations that contain others
+	/// only have this if there's something useful to say, like the name of a variant.
+	pub value: Option<String>,
+}
+
+/// An error decoding some bytes, along with the byte offset at which it occurred.
+#[derive(Debug, thiserror::Error)]
+#[error("Failed to decode at byte {offset}: {error}")]
+pub struct AnnotatedError {
+	/// The offset of the first byte of the item that we failed to decode.
+	pub offset: usize,
+	/// The reason that decoding failed.
+	#[source]
+	pub error: DecodeError,
+}
+
+/// The result of decoding something, along with annotations describing the bytes that were decoded.
+/// If decoding fails, the annotations describe everything that was decoded up to the point of failure.
+#[derive(Debug)]
+pub struct Annotated<T> {
+	/// The decoded value, or an error.
+	pub result: Result<T, AnnotatedError>,
+	/// Annotations describing the bytes that were decoded, in the order that they were decoded.
+	pub annotations: Vec<Annotation>,
+}
+
+/// Decode a SCALE encoded extrinsic (which is expected to be "unwrapped"; see [`super::decode_unwrapped_extrinsic`]),
+/// recording the range of bytes that each part of it was decoded from. This includes each part of the signature,
+/// each signed extension, the call indexes, and each (nested) argument value. Any bytes left over once the
+/// extrinsic has been decoded are reported as an error.
+///
+/// Pass the result to [`render_hex_dump`] to obtain a human readable explanation of the bytes.
+///
+/// # Example
+///
+/// ```rust
+/// use hex;
+/// use desub_current::{ Metadata, decoder };
+///
+/// let metadata_scale_encoded = include_bytes!("../../tests/data/v14_metadata_polkadot.scale");
+/// let metadata = Metadata::from_bytes(metadata_scale_encoded).unwrap();
+///
+/// let extrinsic_bytes = hex::decode("04480104080c1014").unwrap();
+/// let annotated = decoder::decode_extrinsic_annotated(&metadata, &extrinsic_bytes);
+///
+/// assert!(annotated.result.is_ok());
+/// let call_index = annotated.annotations.iter().find(|a| a.path == "call.call_index").unwrap();
+/// assert_eq!(call_index.range, 2..3);
+///
+/// println!("{}", decoder::render_hex_dump(&extrinsic_bytes, &annotated));
+/// ```
+pub fn decode_extrinsic_annotated<'a>(metadata: &'a Metadata, data: &[u8]) -> Annotated<Extrinsic<'a>> {
+	let mut annotator = Annotator { metadata, total_len: data.len(), annotations: Vec::new() };
+	let remaining = &mut &*data;
+	let result = match annotator.extrinsic(remaining) {
+		Ok(()) if !remaining.is_empty() => Err(AnnotatedError {
+			offset: annotator.offset(remaining),
+			error: DecodeError::ExcessBytes(remaining.len()),
+		}),
+		// We've already walked over the bytes, so this should not fail, but use the
+		// standard decode logic to actually hand back the decoded extrinsic.
+		Ok(()) => {
+			let cursor = &mut &*data;
+			decode_unwrapped_extrinsic(metadata, cursor)
+				.map_err(|error| AnnotatedError { offset: annotator.offset(cursor), error })
+		}
+		Err(e) => Err(e),
+	};
+	Annotated { result, annotations: annotator.annotations }
+}
+
+/// Render an annotated hex dump of some bytes, given the result of decoding them with something
+/// like [`decode_extrinsic_annotated`]. Each line contains the byte range, the bytes themselves
+/// (truncated if there are lots of them), and a description of what they were decoded into. If
+/// decoding failed, the remaining bytes and the error are printed last.
+pub fn render_hex_dump<T>(bytes: &[u8], annotated: &Annotated<T>) -> String {
+	let mut out = String::new();
+	for annotation in &annotated.annotations {
+		let indent = "  ".repeat(annotation.depth);
+		let value = annotation.value.as_ref().map(|v| format!(": {v}")).unwrap_or_default();
+		let hex = hex_snippet(bytes.get(annotation.range.clone()).unwrap_or_default());
+		let (start, end) = (annotation.range.start, annotation.range.end);
+		let _ = writeln!(out, "{start:>6}..{end:<6} {hex:<36} {indent}{}{value}", annotation.path);
+	}
+	if let Err(e) = &annotated.result {
+		let hex = hex_snippet(bytes.get(e.offset..).unwrap_or_default());
+		let _ = writeln!(out, "{:>6}..{:<6} {hex:<36} error: {}", e.offset, bytes.len(), e.error);
+	}
+	out
+}
+
+fn hex_snippet(bytes: &[u8]) -> String {
+	if bytes.len() > HEX_DUMP_MAX_BYTES {
+		format!("{}..", hex::encode(&bytes[..HEX_DUMP_MAX_BYTES]))
+	} else {
+		hex::encode(bytes)
+	}
+}
+
+// Walks over some bytes, recording annotations as it goes.
+struct Annotator<'m> {
+	metadata: &'m Metadata,
+	total_len: usize,
+	annotations: Vec<Annotation>,
+}
+
+impl<'m> Annotator<'m> {
+	fn extrinsic(&mut self, data: &mut &[u8]) -> Result<(), AnnotatedError> {
+		let kind = self.leaf("version", 0, data, |data| {
+			let byte = *data.first().ok_or(DecodeError::EarlyEof("extrinsic byte length should be > 0"))?;
+			let (version, kind) = extrinsic_version_and_kind(byte)?;
+			*data = &data[1..];
+			Ok((format!("{version} ({kind:?})"), kind))
+		})?;
+
+		match kind {
+			ExtrinsicKind::Bare => {}
+			ExtrinsicKind::Signed => {
+				let start = self.node("signature", 0, data);
+				let metadata = self.metadata;
+				self.leaf("signature.address", 1, data, |data| {
+					let address = decode_address(metadata, data)?;
+					Ok((format!("{address:?}"), ()))
+				})?;
+				self.leaf("signature.signature", 1, data, |data| {
+					let signature = decode_signature_only(metadata, data)?;
+					Ok((format!("{signature:?}"), ()))
+				})?;
+				self.extensions("signature.extensions", 1, data)?;
+				self.end_node(start, data);
+			}
+			ExtrinsicKind::General => {
+				let start = self.node("extensions", 0, data);
+				self.leaf("extensions.version", 1, data, |data| {
+					let version = u8::decode(data)?;
+					Ok((version.to_string(), ()))
+				})?;
+				self.extensions("extensions", 1, data)?;
+				self.end_node(start, data);
+			}
+		}
+
+		self.call_data(data)
+	}
+
+	fn extensions(&mut self, path: &str, depth: usize, data: &mut &[u8]) -> Result<(), AnnotatedError> {
+		for ext in self.metadata.extrinsic().signed_extensions() {
+			self.value(&format!("{path}.{}", ext.identifier), depth, ext.ty.id, data)?;
+		}
+		Ok(())
+	}
+
+	fn call_data(&mut self, data: &mut &[u8]) -> Result<(), AnnotatedError> {
+		let start = self.node("call", 0, data);
+		let pallet_index = self.leaf("call.pallet_index", 1, data, |data| {
+			let index = u8::decode(data)?;
+			Ok((index.to_string(), index))
+		})?;
+		let call_index = self.leaf("call.call_index", 1, data, |data| {
+			let index = u8::decode(data)?;
+			Ok((index.to_string(), index))
+		})?;
+
+		let (pallet_name, variant) =
+			self.metadata.call_variant_by_enum_index(pallet_index, call_index).ok_or(AnnotatedError {
+				offset: self.offset(data) - 2,
+				error: DecodeError::CannotFindCall(pallet_index, call_index),
+			})?;
+		self.annotations[start].value = Some(format!("{pallet_name}.{}", variant.name));
+
+		self.fields("call.args", 1, &variant.fields, data)?;
+		self.end_node(start, data);
+		Ok(())
+	}
+
+	fn fields(
+		&mut self,
+		path: &str,
+		depth: usize,
+		fields: &[Field<PortableForm>],
+		data: &mut &[u8],
+	) -> Result<(), AnnotatedError> {
+		for (idx, field) in fields.iter().enumerate() {
+			let name = field.name.clone().unwrap_or_else(|| idx.to_string());
+			self.value(&format!("{path}.{name}"), depth, field.ty.id, data)?;
+		}
+		Ok(())
+	}
+
+	// Walk over a value of the given type, annotating each part of it.
+	fn value(&mut self, path: &str, depth: usize, ty: TypeId, data: &mut &[u8]) -> Result<(), AnnotatedError> {
+		let metadata = self.metadata;
+		let type_def = match metadata.resolve(ty) {
+			Some(ty) => &ty.type_def,
+			None => return Err(AnnotatedError { offset: self.offset(data), error: DecodeError::CannotFindType(ty) }),
+		};
+
+		match type_def {
+			TypeDef::Composite(composite) => {
+				let start = self.node(path, depth, data);
+				self.fields(path, depth + 1, &composite.fields, data)?;
+				self.end_node(start, data);
+			}
+			TypeDef::Variant(variant) => {
+				// If we can't find the variant, decoding the value will give back an appropriate error.
+				let Some(variant) = data.first().and_then(|idx| variant.variants.iter().find(|v| v.index == *idx))
+				else {
+					return self.leaf_value(path, depth, ty, data);
+				};
+				let start = self.node(path, depth, data);
+				self.annotations[start].value = Some(variant.name.clone());
+				self.leaf(&format!("{path}.variant_index"), depth + 1, data, |data| {
+					let index = u8::decode(data)?;
+					Ok((index.to_string(), ()))
+				})?;
+				self.fields(path, depth + 1, &variant.fields, data)?;
+				self.end_node(start, data);
+			}
+			TypeDef::Sequence(seq) if !self.is_u8(seq.type_param.id) => {
+				let item_ty = seq.type_param.id;
+				let zero_sized = self.is_zero_sized(item_ty);
+				let start = self.node(path, depth, data);
+				let len = self.leaf(&format!("{path}.length"), depth + 1, data, |data| {
+					let len = <Compact<u32>>::decode(data)?.0;
+					// Each item takes up at least one byte unless it's zero sized, so we can reject
+					// lengths that can't possibly fit before trying to walk over the items.
+					if zero_sized && len > MAX_ZERO_SIZED_SEQUENCE_LEN {
+						return Err(DecodeError::ZeroSizedSequenceTooLong(len));
+					}
+					if !zero_sized && len as usize > data.len() {
+						return Err(DecodeError::EarlyEof("not enough bytes for sequence items"));
+					}
+					Ok((len.to_string(), len))
+				})?;
+				if zero_sized {
+					// Zero sized items all look the same and take up no bytes, so annotate them together.
+					if len > 0 {
+						self.leaf(&format!("{path}[0..{len}]"), depth + 1, data, |data| {
+							let value = decode_value_by_id(metadata, item_ty, data)?;
+							Ok((format!("{len} x {value}"), ()))
+						})?;
+					}
+				} else {
+					for idx in 0..len {
+						self.value(&format!("{path}[{idx}]"), depth + 1, item_ty, data)?;
+					}
+				}
+				self.end_node(start, data);
+			}
+			TypeDef::Array(arr) if !self.is_u8(arr.type_param.id) => {
+				let start = self.node(path, depth, data);
+				for idx in 0..arr.len {
+					self.value(&format!("{path}[{idx}]"), depth + 1, arr.type_param.id, data)?;
+				}
+				self.end_node(start, data);
+			}
+			TypeDef::Tuple(tuple) if !tuple.fields.is_empty() => {
+				let start = self.node(path, depth, data);
+				for (idx, field) in tuple.fields.iter().enumerate() {
+					self.value(&format!("{path}.{idx}"), depth + 1, field.id, data)?;
+				}
+				self.end_node(start, data);
+			}
+			// Byte sequences and arrays are annotated as a single hex encoded value.
+			TypeDef::Sequence(_) => self.leaf(path, depth, data, |data| {
+				let bytes = <Vec<u8>>::decode(data)?;
+				Ok((format!("0x{}", hex::encode(bytes)), ()))
+			})?,
+			TypeDef::Array(arr) => self.leaf(path, depth, data, |data| {
+				let len = arr.len as usize;
+				if data.len() < len {
+					return Err(DecodeError::EarlyEof("not enough bytes for byte array"));
+				}
+				let (bytes, rest) = data.split_at(len);
+				*data = rest;
+				Ok((format!("0x{}", hex::encode(bytes)), ()))
+			})?,
+			// Primitives, compact values, bit sequences and so on are annotated as a single value.
+			_ => self.leaf_value(path, depth, ty, data)?,
+		}
+		Ok(())
+	}
+
+	// Decode a single value of the given type, annotating it in its entirety.
+	fn leaf_value(&mut self, path: &str, depth: usize, ty: TypeId, data: &mut &[u8]) -> Result<(), AnnotatedError> {
+		let metadata = self.metadata;
+		self.leaf(path, depth, data, |data| {
+			let value = decode_value_by_id(metadata, ty, data)?;
+			Ok((value.to_string(), ()))
+		})
+	}
+
+	fn is_u8(&self, ty: TypeId) -> bool {
+		matches!(self.metadata.resolve(ty).map(|t| &t.type_def), Some(TypeDef::Primitive(TypeDefPrimitive::U8)))
+	}
+
+	// Does a value of this type always encode to zero bytes, like `()` or an empty struct?
+	fn is_zero_sized(&self, ty: TypeId) -> bool {
+		match self.metadata.resolve(ty).map(|t| &t.type_def) {
+			Some(TypeDef::Composite(composite)) => composite.fields.iter().all(|f| self.is_zero_sized(f.ty.id)),
+			Some(TypeDef::Tuple(tuple)) => tuple.fields.iter().all(|f| self.is_zero_sized(f.id)),
+			Some(TypeDef::Array(arr)) => arr.len == 0 || self.is_zero_sized(arr.type_param.id),
+			_ => false,
+		}
+	}
+
+	// Decode something using the function provided, recording an annotation for the bytes that it consumed,
+	// or returning an error pointing at the start of those bytes if it fails.
+	fn leaf<R>(
+		&mut self,
+		path: &str,
+		depth: usize,
+		data: &mut &[u8],
+		decode: impl FnOnce(&mut &[u8]) -> Result<(String, R), DecodeError>,
+	) -> Result<R, AnnotatedError> {
+		let start = self.offset(data);
+		let (value, res) = decode(data).map_err(|error| AnnotatedError { offset: start, error })?;
+		let end = self.offset(data);
+		self.annotations.push(Annotation { range: start..end, path: path.to_owned(), depth, value: Some(value) });
+		Ok(res)
+	}
+
+	// Begin an annotation that will contain other annotations, returning its index so that
+	// we can fill in the end of its range with `end_node` once the contents have been decoded.
+	fn node(&mut self, path: &str, depth: usize, data: &[u8]) -> usize {
+		let idx = self.annotations.len();
+		let start = self.offset(data);
+		self.annotations.push(Annotation { range: start..start, path: path.to_owned(), depth, value: None });
+		idx
+	}
+
+	fn end_node(&mut self, idx: usize, data: &[u8]) {
+		self.annotations[idx].range.end = self.offset(data);
+	}
+
+	fn offset(&self, data: &[u8]) -> usize {
+		self.total_len - data.len()
+	}
+}
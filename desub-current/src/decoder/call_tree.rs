@@ -0,0 +1,192 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Calls like `Utility.batch`, `Proxy.proxy`, `Sudo.sudo` and `Multisig.as_multi` take other calls as
+//! arguments. This module exposes [`decode_call_tree`], which walks the arguments of some decoded
+//! [`CallData`] and pulls out each of these nested calls, so that they can be inspected in the same
+//! way as the outermost call.
+
+use super::{decode_call_data, CallData};
+use crate::metadata::Metadata;
+use crate::TypeId;
+use scale_value::{Composite, Primitive, Value, ValueDef};
+use serde::Serialize;
+use std::borrow::Cow;
+
+/// A call, and any calls nested within its arguments.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CallNode<'a> {
+	/// The name of the pallet
+	#[serde(borrow)]
+	pub pallet_name: Cow<'a, str>,
+	/// The type information for this call (including the name
+	/// of the call and information about each argument)
+	pub ty: Cow<'a, scale_info::Variant<scale_info::form::PortableForm>>,
+	/// The decoded argument data
+	pub arguments: Vec<Value<TypeId>>,
+	/// How deeply nested this call is; the outermost call has a depth of 0.
+	pub depth: usize,
+	/// A dot separated path describing where this call was found, like `call.args.calls[1]`.
+	/// The outermost call has the path `call`.
+	pub path: String,
+	/// Calls found in the arguments of this one, in the order that they appear.
+	pub children: Vec<CallNode<'a>>,
+}
+
+impl<'a> CallNode<'a> {
+	/// Iterate over this call and every call nested within it, depth first.
+	pub fn iter(&self) -> impl Iterator<Item = &CallNode<'a>> {
+		let mut stack = vec![self];
+		std::iter::from_fn(move || {
+			let node = stack.pop()?;
+			stack.extend(node.children.iter().rev());
+			Some(node)
+		})
+	}
+
+	pub fn into_owned(self) -> CallNode<'static> {
+		CallNode {
+			pallet_name: Cow::Owned(self.pallet_name.into_owned()),
+			ty: Cow::Owned(self.ty.into_owned()),
+			arguments: self.arguments,
+			depth: self.depth,
+			path: self.path,
+			children: self.children.into_iter().map(|c| c.into_owned()).collect(),
+		}
+	}
+}
+
+/// Walk the arguments of some decoded call data, returning a tree of every call nested within it.
+///
+/// Any argument (or part of an argument, such as the items in a `Vec`) whose type is the runtime call
+/// type is treated as a nested call. Arguments whose type is named `OpaqueCall` (as used by older versions
+/// of `Multisig.as_multi`) hold SCALE encoded call data, and are decoded into nested calls where possible.
+///
+/// # Example
+///
+/// ```rust
+/// use desub_current::{ Metadata, decoder };
+///
+/// let metadata_scale_encoded = include_bytes!("../../tests/data/v14_metadata_polkadot.scale");
+/// let metadata = Metadata::from_bytes(metadata_scale_encoded).unwrap();
+///
+/// // Utility.batch([System.fill_block(1234), Auctions.bid(1, 2, 3, 4, 5)]):
+/// let call_bytes = hex::decode("1a00080000d2040000480104080c1014").unwrap();
+/// let call_data = decoder::decode_call_data(&metadata, &mut &*call_bytes).unwrap();
+///
+/// let tree = decoder::decode_call_tree(&metadata, &call_data);
+/// let calls: Vec<_> = tree.iter().map(|c| (c.depth, &*c.ty.name, c.path.as_str())).collect();
+/// assert_eq!(calls, vec![
+///     (0, "batch", "call"),
+///     (1, "fill_block", "call.args.calls[0]"),
+///     (1, "bid", "call.args.calls[1]"),
+/// ]);
+/// ```
+pub fn decode_call_tree<'a>(metadata: &'a Metadata, call_data: &CallData<'a>) -> CallNode<'a> {
+	let walker = Walker { metadata, call_type_id: metadata.extrinsic().call_type_id() };
+	walker.node(call_data.pallet_name.clone(), call_data.ty.clone(), call_data.arguments.clone(), 0, "call".to_owned())
+}
+
+struct Walker<'a> {
+	metadata: &'a Metadata,
+	call_type_id: Option<TypeId>,
+}
+
+impl<'a> Walker<'a> {
+	fn node(
+		&self,
+		pallet_name: Cow<'a, str>,
+		ty: Cow<'a, scale_info::Variant<scale_info::form::PortableForm>>,
+		arguments: Vec<Value<TypeId>>,
+		depth: usize,
+		path: String,
+	) -> CallNode<'a> {
+		let mut children = Vec::new();
+		for (idx, (arg, field)) in arguments.iter().zip(&ty.fields).enumerate() {
+			let name = field.name.as_ref().map(|n| n.to_string()).unwrap_or_else(|| idx.to_string());
+			let arg_path = format!("{path}.args.{name}");
+			if field.type_name.as_deref().is_some_and(|n| n.ends_with("OpaqueCall")) {
+				children.extend(self.opaque_call(arg, depth + 1, arg_path));
+			} else {
+				self.find_calls(arg, depth + 1, arg_path, &mut children);
+			}
+		}
+		CallNode { pallet_name, ty, arguments, depth, path, children }
+	}
+
+	// Look through a value for anything with the runtime call type.
+	fn find_calls(&self, value: &Value<TypeId>, depth: usize, path: String, out: &mut Vec<CallNode<'a>>) {
+		if Some(value.context) == self.call_type_id {
+			out.extend(self.call(value, depth, path));
+			return;
+		}
+		match &value.value {
+			ValueDef::Composite(composite) => self.find_calls_in_composite(composite, depth, &path, out),
+			ValueDef::Variant(variant) => self.find_calls_in_composite(&variant.values, depth, &path, out),
+			_ => {}
+		}
+	}
+
+	fn find_calls_in_composite(
+		&self,
+		composite: &Composite<TypeId>,
+		depth: usize,
+		path: &str,
+		out: &mut Vec<CallNode<'a>>,
+	) {
+		match composite {
+			Composite::Named(fields) => {
+				for (name, value) in fields {
+					self.find_calls(value, depth, format!("{path}.{name}"), out);
+				}
+			}
+			Composite::Unnamed(values) => {
+				for (idx, value) in values.iter().enumerate() {
+					self.find_calls(value, depth, format!("{path}[{idx}]"), out);
+				}
+			}
+		}
+	}
+
+	// A runtime call value looks like `Pallet(call(args..))`.
+	fn call(&self, value: &Value<TypeId>, depth: usize, path: String) -> Option<CallNode<'a>> {
+		let ValueDef::Variant(pallet) = &value.value else { return None };
+		let ValueDef::Variant(call) = &pallet.values.values().next()?.value else { return None };
+		let (pallet_index, variants) = self.metadata.call_variants_by_pallet_name(&pallet.name)?;
+		let call_index = variants.iter().find(|v| v.name == call.name)?.index;
+		let (pallet_name, variant) = self.metadata.call_variant_by_enum_index(pallet_index, call_index)?;
+		let arguments = call.values.values().cloned().collect();
+		Some(self.node(Cow::Borrowed(pallet_name), Cow::Borrowed(variant), arguments, depth, path))
+	}
+
+	// An opaque call is a sequence of bytes holding SCALE encoded call data.
+	fn opaque_call(&self, value: &Value<TypeId>, depth: usize, path: String) -> Option<CallNode<'a>> {
+		let ValueDef::Composite(composite) = &value.value else { return None };
+		let bytes = composite
+			.values()
+			.map(|v| match v.value {
+				ValueDef::Primitive(Primitive::U128(b)) => u8::try_from(b).ok(),
+				_ => None,
+			})
+			.collect::<Option<Vec<u8>>>()?;
+		let data = &mut &*bytes;
+		let call_data = decode_call_data(self.metadata, data).ok()?;
+		if !data.is_empty() {
+			return None;
+		}
+		Some(self.node(call_data.pallet_name, call_data.ty, call_data.arguments, depth, path))
+	}
+}
@@ -0,0 +1,123 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::DecodeError;
+use crate::metadata::Metadata;
+use scale_info::form::PortableForm;
+use scale_value::{Composite, Value, ValueDef};
+use serde::Serialize;
+use std::borrow::Cow;
+
+/// Look up the error that a `DispatchError::Module` refers to, given the index of the pallet that
+/// returned it and the accompanying error bytes.
+///
+/// Older runtimes encode the error as a single `u8`, and newer runtimes as a `[u8; 4]`. In both cases
+/// the first byte is the index of the error variant in the pallet's error enum, so either can be
+/// handed to this function.
+///
+/// # Example
+///
+/// ```rust
+/// use desub_current::{ Metadata, decoder };
+///
+/// let metadata_scale_encoded = include_bytes!("../../tests/data/v14_metadata_polkadot.scale");
+/// let metadata = Metadata::from_bytes(metadata_scale_encoded).unwrap();
+///
+/// let error = decoder::decode_module_error(&metadata, 5, &[2, 0, 0, 0]).unwrap();
+/// assert_eq!(error.pallet_name, "Balances");
+/// assert_eq!(error.ty.name, "InsufficientBalance");
+/// ```
+pub fn decode_module_error<'a>(
+	metadata: &'a Metadata,
+	pallet_index: u8,
+	error: &[u8],
+) -> Result<ModuleError<'a>, DecodeError> {
+	let error_index = *error.first().ok_or(DecodeError::EarlyEof("expected an error index"))?;
+	let not_found = || DecodeError::CannotFindError(pallet_index, error_index);
+
+	let pallet = metadata.pallet_by_index(pallet_index).ok_or_else(not_found)?;
+	let variant = pallet.errors().iter().find(|v| v.index == error_index).ok_or_else(not_found)?;
+
+	Ok(ModuleError { pallet_name: Cow::Borrowed(pallet.name()), ty: Cow::Borrowed(variant) })
+}
+
+/// Given a decoded `DispatchError` value (for instance, the first field of a `System.ExtrinsicFailed`
+/// event), look up the pallet error that it refers to. This returns `Ok(None)` if the dispatch error
+/// is not a `DispatchError::Module`, since only those errors are described by a pallet.
+///
+/// Both the `Module { index, error }` shape of older runtimes and the `Module(ModuleError { index, error })`
+/// shape of newer ones are understood, with the error being either a `u8` or a `[u8; 4]`.
+pub fn decode_dispatch_error<'a, T>(
+	metadata: &'a Metadata,
+	dispatch_error: &Value<T>,
+) -> Result<Option<ModuleError<'a>>, DecodeError> {
+	let ValueDef::Variant(variant) = &dispatch_error.value else {
+		return Err(DecodeError::CannotDecodeDispatchError("expected a variant"));
+	};
+	if variant.name != "Module" {
+		return Ok(None);
+	}
+
+	// Newer runtimes wrap the index and error in a `ModuleError` struct.
+	let mut fields = &variant.values;
+	if let Composite::Unnamed(values) = fields {
+		if let [Value { value: ValueDef::Composite(inner), .. }] = values.as_slice() {
+			fields = inner;
+		}
+	}
+	let Composite::Named(fields) = fields else {
+		return Err(DecodeError::CannotDecodeDispatchError("expected named 'index' and 'error' fields"));
+	};
+	let field = |name: &str| fields.iter().find(|(n, _)| n == name).map(|(_, v)| v);
+
+	let pallet_index = field("index")
+		.and_then(Value::as_u128)
+		.and_then(|n| u8::try_from(n).ok())
+		.ok_or(DecodeError::CannotDecodeDispatchError("expected a u8 'index' field"))?;
+	let error = field("error")
+		.and_then(error_bytes)
+		.ok_or(DecodeError::CannotDecodeDispatchError("expected a u8 or [u8; 4] 'error' field"))?;
+
+	decode_module_error(metadata, pallet_index, &error).map(Some)
+}
+
+// The error is either a single u8 or an array of u8s.
+fn error_bytes<T>(value: &Value<T>) -> Option<Vec<u8>> {
+	match &value.value {
+		ValueDef::Primitive(_) => value.as_u128().and_then(|n| u8::try_from(n).ok()).map(|n| vec![n]),
+		ValueDef::Composite(Composite::Unnamed(values)) => {
+			values.iter().map(|v| v.as_u128().and_then(|n| u8::try_from(n).ok())).collect()
+		}
+		_ => None,
+	}
+}
+
+/// The pallet error that a `DispatchError::Module` refers to.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ModuleError<'a> {
+	/// The name of the pallet that returned the error
+	#[serde(borrow)]
+	pub pallet_name: Cow<'a, str>,
+	/// The type information for this error (including the name
+	/// of the error and its documentation)
+	pub ty: Cow<'a, scale_info::Variant<PortableForm>>,
+}
+
+impl<'a> ModuleError<'a> {
+	pub fn into_owned(self) -> ModuleError<'static> {
+		ModuleError { pallet_name: Cow::Owned(self.pallet_name.into_owned()), ty: Cow::Owned(self.ty.into_owned()) }
+	}
+}
@@ -0,0 +1,200 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{decode_value_by_id, DecodeError};
+use crate::metadata::Metadata;
+use crate::TypeId;
+use parity_scale_codec::{Compact, Decode, Encode};
+use scale_info::form::PortableForm;
+use scale_value::Value;
+use serde::Serialize;
+use sp_core::H256;
+use std::borrow::Cow;
+
+/// Decode a SCALE encoded vector of event records against the metadata provided. This is the shape of
+/// the data found in the `System.Events` storage entry; a compact encoded count of how many events exist,
+/// followed by each `EventRecord` in turn. Each record is made up of the [`Phase`] that the event was emitted in,
+/// the event itself (see [`decode_event`]), and a vector of topics.
+///
+/// If something goes wrong part way through, the records decoded so far are handed back alongside the error.
+///
+/// # Example
+///
+/// ```rust
+/// use hex;
+/// use desub_current::{ Metadata, decoder };
+///
+/// let metadata_scale_encoded = include_bytes!("../../tests/data/v14_metadata_polkadot.scale");
+/// let metadata = Metadata::from_bytes(metadata_scale_encoded).unwrap();
+///
+/// // A single `Balances.Transfer` event, emitted while applying the extrinsic at index 1:
+/// let events_hex = "0x04000100000005\
+///     02d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d\
+///     8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48\
+///     3930000000000000000000000000000000";
+/// let events_bytes = hex::decode(events_hex.strip_prefix("0x").unwrap()).unwrap();
+/// let events_cursor = &mut &*events_bytes;
+///
+/// let events = decoder::decode_events(&metadata, events_cursor).unwrap();
+///
+/// assert_eq!(events_cursor.len(), 0);
+/// assert_eq!(events.len(), 1);
+/// assert_eq!(events[0].phase, decoder::Phase::ApplyExtrinsic(1));
+/// assert_eq!(events[0].event.pallet_name, "Balances");
+/// assert_eq!(&*events[0].event.ty.name, "Transfer");
+/// ```
+pub fn decode_events<'a>(
+	metadata: &'a Metadata,
+	data: &mut &[u8],
+) -> Result<Vec<EventRecord<'a>>, (Vec<EventRecord<'a>>, DecodeError)> {
+	// Decode against a copy of the cursor, so that we only move the one handed to us on success.
+	let mut cursor: &[u8] = data;
+	let len = match <Compact<u32>>::decode(&mut cursor) {
+		Ok(len) => len.0,
+		Err(e) => return Err((Vec::new(), e.into())),
+	};
+
+	log::trace!("Decoding {} Total Events.", len);
+
+	// The length is untrusted, so don't pre-allocate based on it.
+	let mut out = Vec::new();
+	for _ in 0..len {
+		match decode_event_record(metadata, &mut cursor) {
+			Ok(record) => out.push(record),
+			Err(e) => return Err((out, e)),
+		}
+	}
+
+	// Shift our externally provided data cursor forwards to the right spot,
+	// so that one can continue to decode more bytes if there are any:
+	*data = cursor;
+
+	Ok(out)
+}
+
+/// Decode a single SCALE encoded `EventRecord`; that is, a [`Phase`], followed by the event
+/// (see [`decode_event`]), followed by a vector of topics.
+fn decode_event_record<'a>(metadata: &'a Metadata, data: &mut &[u8]) -> Result<EventRecord<'a>, DecodeError> {
+	let phase = Phase::decode(data)?;
+	let event = decode_event(metadata, data)?;
+	let topics = <Vec<H256>>::decode(data)?;
+
+	Ok(EventRecord { phase, event, topics })
+}
+
+/// Decode a single SCALE encoded event. Much like call data, this is expected to take the form of
+/// `(u8, u8, fields)`, where the `u8`s determine the pallet and event variant, and then the fields
+/// according to that variant are expected to follow.
+///
+/// # Example
+///
+/// ```rust
+/// use hex;
+/// use desub_current::{ Metadata, decoder };
+///
+/// let metadata_scale_encoded = include_bytes!("../../tests/data/v14_metadata_polkadot.scale");
+/// let metadata = Metadata::from_bytes(metadata_scale_encoded).unwrap();
+///
+/// // `System.ExtrinsicSuccess` with a weight of 0, `DispatchClass::Normal` and `Pays::Yes`:
+/// let event_hex = "0x000000000000000000000000";
+/// let event_bytes = hex::decode(event_hex.strip_prefix("0x").unwrap()).unwrap();
+/// let event_cursor = &mut &*event_bytes;
+///
+/// let event = decoder::decode_event(&metadata, event_cursor).unwrap();
+///
+/// assert_eq!(event_cursor.len(), 0);
+/// assert_eq!(event.pallet_name, "System");
+/// assert_eq!(&*event.ty.name, "ExtrinsicSuccess");
+/// ```
+pub fn decode_event<'a>(metadata: &'a Metadata, data: &mut &[u8]) -> Result<Event<'a>, DecodeError> {
+	// Pluck out the u8's representing the pallet and event enum next.
+	if data.len() < 2 {
+		return Err(DecodeError::EarlyEof("expected at least 2 more bytes for the pallet/event index"));
+	}
+	let pallet_index = u8::decode(data)?;
+	let event_index = u8::decode(data)?;
+	log::trace!("pallet index: {}, event index: {}", pallet_index, event_index);
+
+	// Work out which event the data represents and get type info for it:
+	let (pallet_name, variant) = match metadata.event_variant_by_enum_index(pallet_index, event_index) {
+		Some(event) => event,
+		None => return Err(DecodeError::CannotFindEvent(pallet_index, event_index)),
+	};
+
+	// Decode each of the fields in the event:
+	let fields = variant
+		.fields
+		.iter()
+		.map(|field| {
+			let id = field.ty.id;
+			decode_value_by_id(metadata, id, data).map_err(DecodeError::DecodeValueError)
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+
+	Ok(Event { pallet_name: Cow::Borrowed(pallet_name), ty: Cow::Borrowed(variant), fields })
+}
+
+/// The phase of block execution in which an event was emitted.
+#[derive(Serialize, Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+	/// Applying the extrinsic at the given index.
+	ApplyExtrinsic(u32),
+	/// Finalizing the block.
+	Finalization,
+	/// Initializing the block.
+	Initialization,
+}
+
+/// A decoded event and associated type information.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Event<'a> {
+	/// The name of the pallet that emitted the event
+	#[serde(borrow)]
+	pub pallet_name: Cow<'a, str>,
+	/// The type information for this event (including the name
+	/// of the event and information about each field)
+	pub ty: Cow<'a, scale_info::Variant<PortableForm>>,
+	/// The decoded field data
+	pub fields: Vec<Value<TypeId>>,
+}
+
+impl<'a> Event<'a> {
+	pub fn into_owned(self) -> Event<'static> {
+		Event {
+			pallet_name: Cow::Owned(self.pallet_name.into_owned()),
+			ty: Cow::Owned(self.ty.into_owned()),
+			fields: self.fields,
+		}
+	}
+}
+
+/// The result of successfully decoding an event record.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EventRecord<'a> {
+	/// The phase of block execution in which the event was emitted.
+	pub phase: Phase,
+	/// The decoded event and associated type information.
+	#[serde(borrow)]
+	pub event: Event<'a>,
+	/// Any topics associated with the event.
+	pub topics: Vec<H256>,
+}
+
+impl<'a> EventRecord<'a> {
+	pub fn into_owned(self) -> EventRecord<'static> {
+		EventRecord { phase: self.phase, event: self.event.into_owned(), topics: self.topics }
+	}
+}
@@ -0,0 +1,140 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{decode_value_by_id, DecodeError};
+use crate::metadata::{Metadata, MetadataRuntimeApi, MetadataRuntimeApiMethod};
+use crate::TypeId;
+use scale_value::Value;
+use serde::Serialize;
+use std::borrow::Cow;
+
+/// Decode the SCALE encoded input to a runtime API call, as handed to the `state_call` RPC method. The
+/// runtime API method is named as it is in `state_call`, ie `{api}_{method}` (for example
+/// `AccountNonceApi_account_nonce`), and the input is expected to be each of the method parameters in turn.
+///
+/// Runtime APIs are only described by V15 metadata onwards.
+///
+/// # Example
+///
+/// ```rust
+/// use desub_current::{ Metadata, Value, decoder };
+/// use parity_scale_codec::Encode;
+/// # use desub_current::scale_info::meta_type;
+/// # use frame_metadata::{ RuntimeMetadata, v15::* };
+///
+/// // Get hold of some V15 metadata (normally by making an RPC call to the node you want to
+/// // interact with). This describes an `AccountNonceApi_account_nonce(account: [u8; 32]) -> u32`
+/// // runtime API:
+/// # let unit = meta_type::<()>();
+/// # let v15 = RuntimeMetadataV15::new(
+/// #     vec![],
+/// #     ExtrinsicMetadata { version: 4, address_ty: unit, call_ty: unit, signature_ty: unit, extra_ty: unit, signed_extensions: vec![] },
+/// #     unit,
+/// #     vec![RuntimeApiMetadata {
+/// #         name: "AccountNonceApi",
+/// #         methods: vec![RuntimeApiMethodMetadata {
+/// #             name: "account_nonce",
+/// #             inputs: vec![RuntimeApiMethodParamMetadata { name: "account", ty: meta_type::<[u8; 32]>() }],
+/// #             output: meta_type::<u32>(),
+/// #             docs: vec![],
+/// #         }],
+/// #         docs: vec![],
+/// #     }],
+/// #     OuterEnums { call_enum_ty: unit, event_enum_ty: unit, error_enum_ty: unit },
+/// #     CustomMetadata { map: Default::default() },
+/// # );
+/// let metadata = Metadata::from_runtime_metadata(RuntimeMetadata::V15(v15)).unwrap();
+///
+/// // The input handed to `state_call` for this method is just the encoded account:
+/// let input = [1u8; 32].encode();
+/// let cursor = &mut &*input;
+///
+/// let call = decoder::decode_runtime_api_call(&metadata, "AccountNonceApi_account_nonce", cursor)
+///     .expect("can decode runtime API input");
+///
+/// assert!(cursor.is_empty());
+/// assert_eq!(call.api_name, "AccountNonceApi");
+/// assert_eq!(call.method_name, "account_nonce");
+/// assert_eq!(call.inputs[0].0, "account");
+/// assert_eq!(call.inputs[0].1.clone().remove_context(), Value::from_bytes([1u8; 32]));
+/// ```
+pub fn decode_runtime_api_call<'a>(
+	metadata: &'a Metadata,
+	name: &str,
+	data: &mut &[u8],
+) -> Result<RuntimeApiCall<'a>, DecodeError> {
+	let (api, method) = runtime_api_method(metadata, name)?;
+
+	let inputs = method
+		.inputs()
+		.iter()
+		.map(|param| {
+			let value = decode_value_by_id(metadata, param.ty(), data)?;
+			Ok((Cow::Borrowed(param.name()), value))
+		})
+		.collect::<Result<Vec<_>, DecodeError>>()?;
+
+	Ok(RuntimeApiCall { api_name: Cow::Borrowed(api.name()), method_name: Cow::Borrowed(method.name()), inputs })
+}
+
+/// Decode the SCALE encoded output of a runtime API call, as returned from the `state_call` RPC method.
+/// The runtime API method is named in the same way as for [`decode_runtime_api_call`].
+pub fn decode_runtime_api_output(
+	metadata: &Metadata,
+	name: &str,
+	data: &mut &[u8],
+) -> Result<Value<TypeId>, DecodeError> {
+	let (_, method) = runtime_api_method(metadata, name)?;
+	let value = decode_value_by_id(metadata, method.output(), data)?;
+	Ok(value)
+}
+
+// Find a runtime API method given a name like `Core_version`. API names are camel case, and so
+// the first underscore separates the API name from the method name.
+fn runtime_api_method<'a>(
+	metadata: &'a Metadata,
+	name: &str,
+) -> Result<(&'a MetadataRuntimeApi, &'a MetadataRuntimeApiMethod), DecodeError> {
+	let not_found = || DecodeError::CannotFindRuntimeApiMethod(name.to_owned());
+	let (api_name, method_name) = name.split_once('_').ok_or_else(not_found)?;
+	let api = metadata.runtime_api(api_name).ok_or_else(not_found)?;
+	let method = api.method(method_name).ok_or_else(not_found)?;
+	Ok((api, method))
+}
+
+/// The decoded input to a runtime API call.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct RuntimeApiCall<'a> {
+	/// The name of the runtime API, like `AccountNonceApi`.
+	#[serde(borrow)]
+	pub api_name: Cow<'a, str>,
+	/// The name of the method in the runtime API, like `account_nonce`.
+	#[serde(borrow)]
+	pub method_name: Cow<'a, str>,
+	/// The name and decoded value of each input parameter.
+	#[serde(borrow)]
+	pub inputs: Vec<(Cow<'a, str>, Value<TypeId>)>,
+}
+
+impl<'a> RuntimeApiCall<'a> {
+	pub fn into_owned(self) -> RuntimeApiCall<'static> {
+		RuntimeApiCall {
+			api_name: Cow::Owned(self.api_name.into_owned()),
+			method_name: Cow::Owned(self.method_name.into_owned()),
+			inputs: self.inputs.into_iter().map(|(k, v)| (Cow::Owned(k.into_owned()), v)).collect(),
+		}
+	}
+}
@@ -1,7 +1,7 @@
 use super::Value;
 use crate::metadata::{Metadata, StorageLocation};
-use crate::{ScaleInfoTypeId, TypeId};
-use frame_metadata::v14::StorageEntryType as FrameStorageEntryType;
+use crate::TypeId;
+use frame_metadata::v14::{StorageEntryModifier, StorageEntryType as FrameStorageEntryType};
 use serde::Serialize;
 use sp_core::twox_128;
 use std::borrow::Cow;
@@ -37,6 +37,10 @@ pub enum StorageDecodeError {
 	PrefixNotFound,
 	#[error("Couldn't find a storage entry corresponding to the name hash provided in the data")]
 	NameNotFound,
+	#[error("Couldn't decode the storage value: {decode_error}")]
+	CouldNotDecodeValue { decode_error: super::DecodeValueError },
+	#[error("Failed to decode the storage value: {0} bytes of the input were not consumed")]
+	ExcessValueBytes(usize),
 }
 
 impl StorageDecoder {
@@ -94,7 +98,7 @@ impl StorageDecoder {
 			FrameStorageEntryType::Map { hashers, key, value } => {
 				// We'll consume some more data based on the hashers.
 				// First, get the type information that we need ready.
-				let keys = storage_map_key_to_type_id_vec(metadata, key);
+				let keys = metadata.storage_map_key_tys(hashers, *key);
 				if keys.len() != hashers.len() {
 					panic!(
 						"Metadata inconsistency: keys and hashers for storage lookup {}.{} don't line up",
@@ -162,6 +166,50 @@ impl StorageDecoder {
 		}
 	}
 
+	/// Decode the SCALE encoded bytes representing a storage entry lookup as per [`StorageDecoder::decode_key`],
+	/// and then decode the value found at that location as per [`StorageDecoder::decode_value`].
+	#[allow(clippy::type_complexity)]
+	pub fn decode_key_and_value<'m, 'b>(
+		&self,
+		metadata: &'m Metadata,
+		key: &mut &'b [u8],
+		value: Option<&[u8]>,
+	) -> Result<(StorageEntry<'m, 'b>, Option<Value<TypeId>>), StorageDecodeError> {
+		let entry = self.decode_key(metadata, key)?;
+		let value = self.decode_value(metadata, &entry, value)?;
+		Ok((entry, value))
+	}
+
+	/// Decode the SCALE encoded value found at the storage location given by some decoded [`StorageEntry`].
+	/// If no value is provided, we return `None` if the storage entry is optional, and otherwise decode the
+	/// default value for the entry from the metadata. All of the value bytes are expected to be consumed.
+	pub fn decode_value(
+		&self,
+		metadata: &Metadata,
+		entry: &StorageEntry,
+		value: Option<&[u8]>,
+	) -> Result<Option<Value<TypeId>>, StorageDecodeError> {
+		let prefix_hash = twox_128(entry.prefix.as_bytes());
+		let name_hash = twox_128(entry.name.as_bytes());
+		let location = self.hashes_to_location(&prefix_hash, &name_hash)?;
+		let entry_metadata = metadata.storage_entry(location).metadata;
+
+		let bytes = match (value, &entry_metadata.modifier) {
+			(Some(bytes), _) => bytes,
+			(None, StorageEntryModifier::Default) => &entry_metadata.default,
+			(None, StorageEntryModifier::Optional) => return Ok(None),
+		};
+
+		let cursor = &mut &*bytes;
+		let value = super::decode_value_by_id(metadata, entry.ty, cursor)
+			.map_err(|e| StorageDecodeError::CouldNotDecodeValue { decode_error: e })?;
+		if !cursor.is_empty() {
+			return Err(StorageDecodeError::ExcessValueBytes(cursor.len()));
+		}
+
+		Ok(Some(value))
+	}
+
 	// Reverse the prefix+name hashing (which takes the form of `twox_128(prefix) + twox_128(name)`)
 	// into a specific storage location, which we can lookup in the Metadata to decode the remaining
 	// bytes.
@@ -169,38 +217,21 @@ impl StorageDecoder {
 		if data.len() < 32 {
 			return Err(StorageDecodeError::NotEnoughBytesForPrefixAndName(data.len()));
 		}
-		let prefix_hash = &data[..16];
-		let name_hash = &data[16..32];
-
-		let entries = self.entries_by_hashed_prefix.get(prefix_hash).ok_or(StorageDecodeError::PrefixNotFound)?;
-		let entry_index = entries.entry_by_hashed_name.get(name_hash).ok_or(StorageDecodeError::NameNotFound)?;
+		let location = self.hashes_to_location(&data[..16], &data[16..32])?;
 
 		// Successfully consumed the prefix and name bytes, so move our cursor.
 		// In the case of errors, we leave the data "unconsumed".
 		*data = &data[32..];
 
-		Ok(StorageLocation { prefix_index: entries.index, entry_index: *entry_index })
+		Ok(location)
 	}
-}
 
-// Metadata info for maps/doublemaps contains a vec of hashers for each key type,
-// and a Type representing the key(s). We expect the number of keys and hashers to
-// line up, so let's resolve the keys into something easier to work with.
-//
-// See https://github.com/paritytech/subxt/blob/793c945fbd2de022f523c39a84ee02609ba423a9/codegen/src/api/storage.rs#L105
-// for another example of this being handled in code.
-fn storage_map_key_to_type_id_vec(metadata: &Metadata, key: &ScaleInfoTypeId) -> Vec<TypeId> {
-	let ty_id = key.id;
-	let ty = match metadata.resolve(ty_id) {
-		Some(ty) => ty,
-		None => panic!("Metadata inconsistency: type #{} not found", ty_id),
-	};
-
-	match &ty.type_def {
-		// Multiple keys:
-		scale_info::TypeDef::Tuple(vals) => vals.fields.iter().map(|f| f.id).collect(),
-		// Single key:
-		_ => vec![ty_id],
+	// Find the storage location corresponding to the twox_128 hashes of some prefix and name.
+	fn hashes_to_location(&self, prefix_hash: &[u8], name_hash: &[u8]) -> Result<StorageLocation, StorageDecodeError> {
+		let entries = self.entries_by_hashed_prefix.get(prefix_hash).ok_or(StorageDecodeError::PrefixNotFound)?;
+		let entry_index = entries.entry_by_hashed_name.get(name_hash).ok_or(StorageDecodeError::NameNotFound)?;
+
+		Ok(StorageLocation { prefix_index: entries.index, entry_index: *entry_index })
 	}
 }
 
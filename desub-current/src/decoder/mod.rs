@@ -20,24 +20,57 @@
 //! See [`decode_extrinsics`], [`decode_extrinsic`], and [`decode_unwrapped_extrinsic`] for the most
 //! common extrinsic decoding needs.
 //!
+//! See [`decode_call_tree`] to find the calls nested within calls like `Utility.batch` or `Proxy.proxy`.
+//!
+//! See [`decode_events`] and [`decode_event`] to decode the events emitted in a block, and
+//! [`decode_dispatch_error`] or [`decode_module_error`] to find out which pallet error a failed extrinsic
+//! returned.
+//!
+//! See [`decode_runtime_api_call`] and [`decode_runtime_api_output`] to decode the inputs and outputs of
+//! runtime API calls made via `state_call`.
+//!
 //! See [`decode_storage()`] and then the documentation on [`StorageDecoder`] to decode storage lookups.
-
+//!
+//! See [`decode_extrinsic_annotated`] and [`render_hex_dump`] to work out which bytes of an extrinsic
+//! were decoded into what, which can help when debugging decode failures.
+
+mod annotate;
+mod call_tree;
+mod decode_errors;
+mod decode_events;
+mod decode_runtime_api;
 mod decode_storage;
 mod extrinsic_bytes;
 
-use crate::metadata::Metadata;
+use crate::metadata::{ExtrinsicAddressType, ExtrinsicSignatureType, Metadata};
 use crate::TypeId;
 use extrinsic_bytes::{AllExtrinsicBytes, ExtrinsicBytesError};
-use parity_scale_codec::{Compact, Decode};
+use parity_scale_codec::{Compact, Decode, Encode};
 use scale_decode::DecodeAsType;
 use scale_value::Value;
 use serde::Serialize;
+use sp_core::{blake2_256, H256};
 use sp_runtime::{AccountId32, MultiAddress, MultiSignature};
 use std::borrow::Cow;
 
 // Re-export the DecodeValueError here, which we expose in our global `DecodeError` enum.
 pub use scale_decode::Error as DecodeValueError;
 
+// Re-export annotated decoding types and functions that are part of our public interface.
+pub use annotate::{decode_extrinsic_annotated, render_hex_dump, Annotated, AnnotatedError, Annotation};
+
+// Re-export the call tree types and functions that are part of our public interface.
+pub use call_tree::{decode_call_tree, CallNode};
+
+// Re-export error related types and functions that are part of our public interface.
+pub use decode_errors::{decode_dispatch_error, decode_module_error, ModuleError};
+
+// Re-export event related types and functions that are part of our public interface.
+pub use decode_events::{decode_event, decode_events, Event, EventRecord, Phase};
+
+// Re-export runtime API related types and functions that are part of our public interface.
+pub use decode_runtime_api::{decode_runtime_api_call, decode_runtime_api_output, RuntimeApiCall};
+
 // Re-export storage related types that are part of our public interface.
 pub use decode_storage::{
 	StorageDecodeError, StorageDecoder, StorageEntry, StorageEntryType, StorageHasher, StorageMapKey,
@@ -59,10 +92,35 @@ pub enum DecodeError {
 	ExcessBytes(usize),
 	#[error("Failed to decode unsupported extrinsic version '{0}'")]
 	CannotDecodeExtrinsicVersion(u8),
+	#[error("Failed to decode extrinsic: type bits {ty:#04b} are not valid for extrinsic version '{version}'")]
+	CannotDecodeExtrinsicType { version: u8, ty: u8 },
 	#[error("Cannot find call corresponding to extrinsic with pallet index {0} and call index {1}")]
 	CannotFindCall(u8, u8),
+	#[error("Cannot find event corresponding to pallet index {0} and event index {1}")]
+	CannotFindEvent(u8, u8),
+	#[error("Cannot find error corresponding to pallet index {0} and error index {1}")]
+	CannotFindError(u8, u8),
+	#[error("Failed to decode dispatch error: {0}")]
+	CannotDecodeDispatchError(&'static str),
 	#[error("Failed to decode extrinsic: cannot find type ID {0}")]
 	CannotFindType(u32),
+	#[error("Cannot find runtime API method '{0}'")]
+	CannotFindRuntimeApiMethod(String),
+	#[error("Failed to decode: a sequence of {0} zero sized values is too long")]
+	ZeroSizedSequenceTooLong(u32),
+}
+
+/// An error decoding a single extrinsic, as returned from [`decode_extrinsics_lenient`].
+#[derive(Debug, thiserror::Error)]
+#[error("Failed to decode extrinsic {index}: {error}")]
+pub struct ExtrinsicDecodeError {
+	/// The index of the extrinsic in the block.
+	pub index: usize,
+	/// The raw bytes of the extrinsic (not including its length prefix).
+	pub bytes: Vec<u8>,
+	/// The reason that decoding failed.
+	#[source]
+	pub error: DecodeError,
 }
 
 /// Decode a single [`Value`] from a piece of scale encoded data, given some metadata and the ID of the type that we
@@ -173,10 +231,11 @@ pub fn decode_extrinsics<'a>(
 		log::trace!("Extrinsic:{:?}", single_extrinsic.bytes());
 
 		let bytes = &mut single_extrinsic.bytes();
-		let ext = match decode_unwrapped_extrinsic(metadata, bytes) {
+		let mut ext = match decode_unwrapped_extrinsic(metadata, bytes) {
 			Ok(ext) => ext,
 			Err(e) => return Err((out, e)),
 		};
+		ext.index = Some(out.len());
 
 		// If decoding didn't consume all extrinsic bytes, something went wrong.
 		// Hand back whatever we have but note the error.
@@ -194,6 +253,75 @@ pub fn decode_extrinsics<'a>(
 	Ok(out)
 }
 
+/// Decode a SCALE encoded vector of extrinsics against the metadata provided, continuing past any extrinsics that
+/// fail to decode. Unlike [`decode_extrinsics`], this hands back a [`Result`] for each extrinsic, and on failure,
+/// the error contains the index and raw bytes of the extrinsic that we could not decode.
+///
+/// An error is returned up front if the bytes do not begin with a compact encoded count of extrinsics. If the
+/// length prefix of an individual extrinsic cannot be decoded, then we cannot find where any subsequent extrinsics
+/// begin, and so the last item returned will be an error containing the remaining bytes.
+///
+/// # Example
+///
+/// ```rust
+/// use hex;
+/// use desub_current::{ Metadata, decoder };
+///
+/// let metadata_scale_encoded = include_bytes!("../../tests/data/v14_metadata_polkadot.scale");
+/// let metadata = Metadata::from_bytes(metadata_scale_encoded).unwrap();
+///
+/// // An Auctions.bid extrinsic, an extrinsic with an unknown call, and then Auctions.bid again:
+/// let extrinsics_hex = "0x0C2004480104080c10140c04ff002004480104080c1014";
+/// let extrinsics_bytes = hex::decode(extrinsics_hex.strip_prefix("0x").unwrap()).unwrap();
+/// let extrinsics_cursor = &mut &*extrinsics_bytes;
+///
+/// let extrinsics = decoder::decode_extrinsics_lenient(&metadata, extrinsics_cursor).unwrap();
+///
+/// assert_eq!(extrinsics_cursor.len(), 0);
+/// assert_eq!(extrinsics.len(), 3);
+/// assert!(extrinsics[0].is_ok());
+/// assert_eq!(extrinsics[1].as_ref().unwrap_err().index, 1);
+/// assert!(extrinsics[2].is_ok());
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn decode_extrinsics_lenient<'a>(
+	metadata: &'a Metadata,
+	data: &mut &[u8],
+) -> Result<Vec<Result<Extrinsic<'a>, ExtrinsicDecodeError>>, DecodeError> {
+	let extrinsic_bytes = AllExtrinsicBytes::new(data)?;
+
+	log::trace!("Decoding {} Total Extrinsics (leniently).", extrinsic_bytes.len());
+
+	// The reported length can't be trusted, but each extrinsic takes up at least one byte.
+	let mut out = Vec::with_capacity(extrinsic_bytes.len().min(data.len()));
+	let mut extrinsics_iter = extrinsic_bytes.iter();
+	for index in 0.. {
+		let single_extrinsic = match extrinsics_iter.next() {
+			Some(Ok(bytes)) => bytes,
+			Some(Err(e)) => {
+				// We can't find where the next extrinsic begins, so we have to stop here.
+				let bytes = extrinsics_iter.remaining_bytes().to_vec();
+				out.push(Err(ExtrinsicDecodeError { index, bytes, error: e.into() }));
+				break;
+			}
+			None => break,
+		};
+
+		let bytes = &mut single_extrinsic.bytes();
+		let res = match decode_unwrapped_extrinsic(metadata, bytes) {
+			Ok(_) if !bytes.is_empty() => Err(DecodeError::ExcessBytes(bytes.len())),
+			Ok(ext) => Ok(Extrinsic { index: Some(index), ..ext }),
+			Err(e) => Err(e),
+		};
+
+		out.push(res.map_err(|error| ExtrinsicDecodeError { index, bytes: single_extrinsic.bytes().to_vec(), error }));
+	}
+
+	*data = extrinsics_iter.remaining_bytes();
+
+	Ok(out)
+}
+
 /// Decode a SCALE encoded extrinsic against the metadata provided. Conceptually, an individual extrinsic is expected
 /// to be represented in terms of a compact encoded count of its length in bytes, and then the actual extrinsic
 /// information (the optional signature and call data).
@@ -260,12 +388,16 @@ pub fn decode_unwrapped_extrinsic<'a>(metadata: &'a Metadata, data: &mut &[u8])
 	if data.is_empty() {
 		return Err(DecodeError::EarlyEof("unwrapped extrinsic byte length should be > 0"));
 	}
+	let extrinsic_bytes = *data;
 
-	// V4 extrinsics (the format we can decode here) are laid out roughly as follows:
+	// V4 and V5 extrinsics (the formats we can decode here) are laid out roughly as follows:
 	//
-	// first byte: abbbbbbb (a = 0 for unsigned, 1 for signed, b = version)
+	// first byte: aabbbbbb (a = extrinsic type, b = version). The extrinsic type is one of:
+	// - 0b00: bare (unsigned, no extensions)
+	// - 0b10: signed (V4 only)
+	// - 0b01: general (V5 only)
 	//
-	// signature, which is made up of (in order):
+	// For signed extrinsics, a signature, which is made up of (in order):
 	// - sp_runtime::MultiAddress enum (sender)
 	// - sp_runtime::MultiSignature enum
 	// - For polkadot, these extensions (but can vary by chain, so we decode generically):
@@ -273,31 +405,60 @@ pub fn decode_unwrapped_extrinsic<'a>(metadata: &'a Metadata, data: &mut &[u8])
 	//   - compact encoded u32 (nonce; prior transaction count)
 	//   - compact encoded u128 (tip paid to block producer/treasury)
 	//
+	// For general extrinsics, a u8 transaction extension version, followed by the
+	// same extensions as above, but no address or signature.
+	//
 	// call, which is made up roughly of:
 	// - u8 enum pallet index (for pallets variant)
 	// - u8 call index (for inner variant)
 	// - call args (types can be pulled from metadata for each arg we expect)
 	//
-	// So, we start by getting the version/signed from the first byte and go from there.
-	let is_signed = data[0] & 0b1000_0000 != 0;
-	let version = data[0] & 0b0111_1111;
+	// So, we start by getting the version/type from the first byte and go from there.
+	let (version, kind) = extrinsic_version_and_kind(data[0])?;
 	*data = &data[1..];
 
-	// We only know how to decode V4 extrinsics at the moment
-	if version != 4 {
-		return Err(DecodeError::CannotDecodeExtrinsicVersion(version));
-	}
-
-	// If the extrinsic is signed, decode the signature next.
-	let signature = match is_signed {
-		true => Some(decode_signature(metadata, data)?),
-		false => None,
+	let (signature, extensions) = match kind {
+		ExtrinsicKind::Bare => (None, None),
+		ExtrinsicKind::Signed => (Some(decode_signature(metadata, data)?), None),
+		ExtrinsicKind::General => (None, Some(decode_general_extensions(metadata, data)?)),
 	};
 
 	// Finally, decode the call data.
 	let call_data = decode_call_data(metadata, data)?;
 
-	Ok(Extrinsic { call_data, signature })
+	// Keep hold of the bytes that made up the extrinsic, and hash them (including the length prefix) as
+	// substrate does to obtain the extrinsic hash.
+	let bytes = extrinsic_bytes[..extrinsic_bytes.len() - data.len()].to_vec();
+	let mut encoded = Compact(bytes.len() as u32).encode();
+	encoded.extend_from_slice(&bytes);
+	let hash = H256(blake2_256(&encoded));
+
+	Ok(Extrinsic {
+		index: None,
+		hash,
+		encoded_len: encoded.len(),
+		bytes,
+		version,
+		kind,
+		call_data,
+		signature,
+		extensions,
+	})
+}
+
+// Interpret the first byte of an extrinsic, returning its version and kind.
+fn extrinsic_version_and_kind(byte: u8) -> Result<(u8, ExtrinsicKind), DecodeError> {
+	let ty = byte >> 6;
+	let version = byte & 0b0011_1111;
+
+	let kind = match (version, ty) {
+		(4 | 5, 0b00) => ExtrinsicKind::Bare,
+		(4, 0b10) => ExtrinsicKind::Signed,
+		(5, 0b01) => ExtrinsicKind::General,
+		(4 | 5, _) => return Err(DecodeError::CannotDecodeExtrinsicType { version, ty }),
+		_ => return Err(DecodeError::CannotDecodeExtrinsicVersion(version)),
+	};
+	Ok((version, kind))
 }
 
 /// Decode SCALE encoded call data. Conceptually, this is expected to take the form of
@@ -330,6 +491,7 @@ pub fn decode_call_data<'a>(metadata: &'a Metadata, data: &mut &[u8]) -> Result<
 	if data.len() < 2 {
 		return Err(DecodeError::EarlyEof("expected at least 2 more bytes for the pallet/call index"));
 	}
+	let call_bytes = *data;
 	let pallet_index = u8::decode(data)?;
 	let call_index = u8::decode(data)?;
 	log::trace!("pallet index: {}, call index: {}", pallet_index, call_index);
@@ -350,7 +512,9 @@ pub fn decode_call_data<'a>(metadata: &'a Metadata, data: &mut &[u8]) -> Result<
 		})
 		.collect::<Result<Vec<_>, _>>()?;
 
-	Ok(CallData { pallet_name: Cow::Borrowed(pallet_name), ty: Cow::Borrowed(variant), arguments })
+	let bytes = call_bytes[..call_bytes.len() - data.len()].to_vec();
+
+	Ok(CallData { pallet_name: Cow::Borrowed(pallet_name), ty: Cow::Borrowed(variant), arguments, bytes })
 }
 
 /// Decode the SCALE encoded data that, once signed, is used to construct a signed extrinsic. The encoded payload has the following shape:
@@ -372,11 +536,58 @@ pub fn decode_signer_payload<'a>(metadata: &'a Metadata, data: &mut &[u8]) -> Re
 ///
 /// Ordinarily, one should prefer to use [`decode_extrinsic`] directly to decode the entire extrinsic at once.
 pub fn decode_signature<'a>(metadata: &'a Metadata, data: &mut &[u8]) -> Result<ExtrinsicSignature<'a>, DecodeError> {
-	let address = <MultiAddress<AccountId32, u32>>::decode(data)?;
-	let signature = MultiSignature::decode(data)?;
+	let address = decode_address(metadata, data)?;
+	let signature = decode_signature_only(metadata, data)?;
+	let extensions_start = *data;
+	let extensions = decode_signed_extensions(metadata, data)?;
+	let extension_bytes = extensions_start[..extensions_start.len() - data.len()].to_vec();
+
+	Ok(ExtrinsicSignature { address, signature, extensions, extension_bytes })
+}
+
+// Decode the signature (without the address or signed extensions) of a signed extrinsic.
+fn decode_signature_only(metadata: &Metadata, data: &mut &[u8]) -> Result<Signature, DecodeError> {
+	let signature = match metadata.extrinsic().signature() {
+		ExtrinsicSignatureType::MultiSignature { .. } => Signature::MultiSignature(MultiSignature::decode(data)?),
+		ExtrinsicSignatureType::Other(id) => Signature::Value(decode_value_by_id(metadata, id, data)?),
+	};
+	Ok(signature)
+}
+
+// Decode the address of a signed extrinsic. The standard `MultiAddress` is decoded directly, unless the
+// account index is `()` and we're looking at the `Index` variant, which doesn't line up with a `u32` index.
+fn decode_address(metadata: &Metadata, data: &mut &[u8]) -> Result<Address, DecodeError> {
+	const INDEX_VARIANT: u8 = 1;
+	let address = match metadata.extrinsic().address() {
+		ExtrinsicAddressType::MultiAddress { type_id: Some(id), unit_index: true }
+			if data.first() == Some(&INDEX_VARIANT) =>
+		{
+			Address::Value(decode_value_by_id(metadata, id, data)?)
+		}
+		ExtrinsicAddressType::MultiAddress { .. } => {
+			Address::MultiAddress(<MultiAddress<AccountId32, u32>>::decode(data)?)
+		}
+		ExtrinsicAddressType::Other(id) => Address::Value(decode_value_by_id(metadata, id, data)?),
+	};
+	Ok(address)
+}
+
+/// Decode the transaction extension version and extensions of a SCALE encoded general (V5) extrinsic.
+///
+/// V14 metadata only describes a single set of extensions, and so these are used regardless of the version.
+///
+/// Ordinarily, one should prefer to use [`decode_extrinsic`] directly to decode the entire extrinsic at once.
+pub fn decode_general_extensions<'a>(
+	metadata: &'a Metadata,
+	data: &mut &[u8],
+) -> Result<GeneralExtensions<'a>, DecodeError> {
+	if data.is_empty() {
+		return Err(DecodeError::EarlyEof("expected a transaction extension version byte"));
+	}
+	let version = u8::decode(data)?;
 	let extensions = decode_signed_extensions(metadata, data)?;
 
-	Ok(ExtrinsicSignature { address, signature, extensions })
+	Ok(GeneralExtensions { version, extensions })
 }
 
 /// Decode the signed extensions part of a SCALE encoded extrinsic.
@@ -430,6 +641,9 @@ pub struct CallData<'a> {
 	pub ty: Cow<'a, scale_info::Variant<scale_info::form::PortableForm>>,
 	/// The decoded argument data
 	pub arguments: Vec<Value<TypeId>>,
+	/// The raw bytes that the call data was decoded from.
+	#[serde(serialize_with = "serialize_hex")]
+	pub bytes: Vec<u8>,
 }
 
 impl<'a> CallData<'a> {
@@ -438,24 +652,119 @@ impl<'a> CallData<'a> {
 			pallet_name: Cow::Owned(self.pallet_name.into_owned()),
 			ty: Cow::Owned(self.ty.into_owned()),
 			arguments: self.arguments,
+			bytes: self.bytes,
 		}
 	}
+
+	/// The blake2-256 hash of the call data, as used to identify calls in (for instance) multisig operations.
+	pub fn hash(&self) -> H256 {
+		H256(blake2_256(&self.bytes))
+	}
+
+	/// Pair each decoded argument with its name and a Rust-like signature of its type (see
+	/// [`Metadata::type_signature`]). The metadata should be the same as that used to decode the call.
+	pub fn labelled_arguments<'b>(&'b self, metadata: &Metadata) -> Vec<CallArgument<'b>> {
+		self.ty
+			.fields
+			.iter()
+			.zip(&self.arguments)
+			.map(|(field, value)| CallArgument {
+				name: field.name.as_deref(),
+				ty: metadata.type_signature(field.ty.id).unwrap_or_else(|| field.ty.id.to_string()),
+				value,
+			})
+			.collect()
+	}
+}
+
+/// A decoded call argument, labelled with its name and type, as returned from [`CallData::labelled_arguments`].
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CallArgument<'a> {
+	/// The name of the argument, if the call has named arguments.
+	pub name: Option<&'a str>,
+	/// A Rust-like signature of the argument type, like `MultiAddress<AccountId32, ()>`.
+	pub ty: String,
+	/// The decoded argument data.
+	pub value: &'a Value<TypeId>,
+}
+
+/// Serialize raw bytes as a `0x` prefixed hex string, in the same way as the hashes alongside them.
+fn serialize_hex<S: serde::Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
 }
 
 /// The result of successfully decoding an extrinsic.
 #[derive(Serialize, Debug, Clone, PartialEq)]
 pub struct Extrinsic<'a> {
+	/// The index of the extrinsic in the block, if it was decoded via [`decode_extrinsics`]
+	/// or [`decode_extrinsics_lenient`].
+	pub index: Option<usize>,
+	/// The blake2-256 hash of the SCALE encoded extrinsic (including its length prefix).
+	pub hash: H256,
+	/// The length of the SCALE encoded extrinsic in bytes (including its length prefix).
+	pub encoded_len: usize,
+	/// The raw bytes that the extrinsic was decoded from (not including its length prefix).
+	#[serde(serialize_with = "serialize_hex")]
+	pub bytes: Vec<u8>,
+	/// The extrinsic format version (4 or 5).
+	pub version: u8,
+	/// Whether the extrinsic is bare, signed or general.
+	pub kind: ExtrinsicKind,
 	/// Decoded call data and associated type information about the call.
 	#[serde(borrow)]
 	pub call_data: CallData<'a>,
-	/// The signature and signed extensions (if any) associated with the extrinsic
+	/// The signature and signed extensions associated with the extrinsic, if it's signed.
 	#[serde(borrow)]
 	pub signature: Option<ExtrinsicSignature<'a>>,
+	/// The transaction extensions associated with the extrinsic, if it's a general extrinsic.
+	#[serde(borrow)]
+	pub extensions: Option<GeneralExtensions<'a>>,
 }
 
 impl<'a> Extrinsic<'a> {
 	pub fn into_owned(self) -> Extrinsic<'static> {
-		Extrinsic { call_data: self.call_data.into_owned(), signature: self.signature.map(|s| s.into_owned()) }
+		Extrinsic {
+			index: self.index,
+			hash: self.hash,
+			encoded_len: self.encoded_len,
+			bytes: self.bytes,
+			version: self.version,
+			kind: self.kind,
+			call_data: self.call_data.into_owned(),
+			signature: self.signature.map(|s| s.into_owned()),
+			extensions: self.extensions.map(|e| e.into_owned()),
+		}
+	}
+}
+
+/// The kind of an extrinsic, as determined by its first byte.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtrinsicKind {
+	/// An extrinsic with no signature or extensions (known as "unsigned" prior to V5).
+	Bare,
+	/// A V4 extrinsic with an address, signature and signed extensions.
+	Signed,
+	/// A V5 extrinsic with transaction extensions but no signature.
+	General,
+}
+
+/// The transaction extensions embedded in a general (V5) extrinsic.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct GeneralExtensions<'a> {
+	/// The version of the transaction extensions in use.
+	pub version: u8,
+	/// Transaction extensions, which can vary by node. Here, we
+	/// return the name and value of each.
+	#[serde(borrow)]
+	pub extensions: Vec<(Cow<'a, str>, Value<TypeId>)>,
+}
+
+impl<'a> GeneralExtensions<'a> {
+	pub fn into_owned(self) -> GeneralExtensions<'static> {
+		GeneralExtensions {
+			version: self.version,
+			extensions: self.extensions.into_iter().map(|(k, v)| (Cow::Owned(k.into_owned()), v)).collect(),
+		}
 	}
 }
 
@@ -463,14 +772,16 @@ impl<'a> Extrinsic<'a> {
 #[derive(Serialize, Debug, Clone, PartialEq)]
 pub struct ExtrinsicSignature<'a> {
 	/// Address the extrinsic is being sent from
-	#[serde(with = "desub_common::RemoteAddress")]
-	pub address: MultiAddress<AccountId32, u32>,
+	pub address: Address,
 	/// Signature to prove validity
-	pub signature: MultiSignature,
+	pub signature: Signature,
 	/// Signed extensions, which can vary by node. Here, we
 	/// return the name and value of each.
 	#[serde(borrow)]
 	pub extensions: Vec<(Cow<'a, str>, Value<TypeId>)>,
+	/// The raw bytes that the signed extensions were decoded from, which form part of the signer payload.
+	#[serde(skip)]
+	pub extension_bytes: Vec<u8>,
 }
 
 impl<'a> ExtrinsicSignature<'a> {
@@ -479,6 +790,49 @@ impl<'a> ExtrinsicSignature<'a> {
 			address: self.address,
 			signature: self.signature,
 			extensions: self.extensions.into_iter().map(|(k, v)| (Cow::Owned(k.into_owned()), v)).collect(),
+			extension_bytes: self.extension_bytes,
+		}
+	}
+}
+
+/// The address that a signed extrinsic is sent from. The standard `MultiAddress` type is decoded directly,
+/// and any other address type is decoded into a [`Value`] according to the metadata.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Address {
+	/// A standard `MultiAddress`.
+	MultiAddress(#[serde(with = "desub_common::RemoteAddress")] MultiAddress<AccountId32, u32>),
+	/// Some other address, such as an Ethereum style `AccountId20`.
+	Value(Value<TypeId>),
+}
+
+impl Address {
+	/// Return the standard `MultiAddress`, if that's what this is.
+	pub fn as_multi_address(&self) -> Option<&MultiAddress<AccountId32, u32>> {
+		match self {
+			Address::MultiAddress(address) => Some(address),
+			Address::Value(_) => None,
+		}
+	}
+}
+
+/// The signature of a signed extrinsic. The standard `MultiSignature` type is decoded directly,
+/// and any other signature type is decoded into a [`Value`] according to the metadata.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Signature {
+	/// A standard `MultiSignature`.
+	MultiSignature(MultiSignature),
+	/// Some other signature, such as an Ethereum style ECDSA signature.
+	Value(Value<TypeId>),
+}
+
+impl Signature {
+	/// Return the standard `MultiSignature`, if that's what this is.
+	pub fn as_multi_signature(&self) -> Option<&MultiSignature> {
+		match self {
+			Signature::MultiSignature(signature) => Some(signature),
+			Signature::Value(_) => None,
 		}
 	}
 }
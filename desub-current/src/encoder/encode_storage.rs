@@ -0,0 +1,143 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::EncodeValueError;
+use crate::metadata::{Metadata, StorageLocation};
+use frame_metadata::v14::{StorageEntryType as FrameStorageEntryType, StorageHasher as FrameStorageHasher};
+use scale_encode::EncodeAsType;
+use scale_value::Value;
+use sp_core::{blake2_128, blake2_256, twox_128, twox_256, twox_64};
+use std::collections::HashMap;
+
+/// This struct is capable of building SCALE encoded storage keys.
+pub struct StorageEncoder {
+	/// We can find the storage entries for a given prefix by name:
+	entries_by_prefix: HashMap<String, StorageEntries>,
+}
+
+struct StorageEntries {
+	/// The index of the storage entry as stored in the metadata used to
+	/// generate this.
+	index: usize,
+	/// Within this pallet/prefix, we can find the sub-index of each storage entry
+	/// by name:
+	entry_by_name: HashMap<String, usize>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum StorageEncodeError {
+	#[error("Couldn't find a storage entry with the prefix '{0}'")]
+	PrefixNotFound(String),
+	#[error("Couldn't find a storage entry with the name '{0}'")]
+	NameNotFound(String),
+	#[error("Too many keys were provided; got {got} but the storage entry has at most {max}")]
+	TooManyKeys { got: usize, max: usize },
+	#[error("Couldn't encode the value for key {key}: {encode_error}")]
+	CouldNotEncodeKey { key: usize, encode_error: EncodeValueError },
+}
+
+impl StorageEncoder {
+	/// Call [`super::encode_storage()`] to construct a [`StorageEncoder`].
+	pub(super) fn generate_from_metadata(metadata: &Metadata) -> StorageEncoder {
+		let entries_by_prefix = metadata
+			.storage_entries()
+			.enumerate()
+			.map(|(index, entries)| {
+				let entry_by_name = entries
+					.entries()
+					.enumerate()
+					.map(|(entry_index, entry)| (entry.name.clone(), entry_index))
+					.collect();
+				(entries.prefix().to_owned(), StorageEntries { index, entry_by_name })
+			})
+			.collect();
+
+		StorageEncoder { entries_by_prefix }
+	}
+
+	/// Build the SCALE encoded bytes for a storage entry lookup. These take the form
+	/// `twox_128(prefix) + twox_128(name) + hashed keys`, where each of the `keys` provided is encoded
+	/// according to the type information in the metadata, and then hashed using the corresponding hasher.
+	///
+	/// Fewer keys than the storage entry expects can be provided, in which case the result is a partial
+	/// key that can be used to iterate over all of the entries that share that prefix.
+	pub fn encode_key<T>(
+		&self,
+		metadata: &Metadata,
+		prefix: &str,
+		name: &str,
+		keys: &[Value<T>],
+	) -> Result<Vec<u8>, StorageEncodeError> {
+		let location = self.prefix_and_name_to_location(prefix, name)?;
+		let storage_entry = metadata.storage_entry(location);
+
+		let mut bytes = twox_128(prefix.as_bytes()).to_vec();
+		bytes.extend(twox_128(name.as_bytes()));
+
+		match &storage_entry.metadata.ty {
+			FrameStorageEntryType::Plain(_) => {
+				if !keys.is_empty() {
+					return Err(StorageEncodeError::TooManyKeys { got: keys.len(), max: 0 });
+				}
+			}
+			FrameStorageEntryType::Map { hashers, key, .. } => {
+				let key_tys = metadata.storage_map_key_tys(hashers, *key);
+				if keys.len() > hashers.len() {
+					return Err(StorageEncodeError::TooManyKeys { got: keys.len(), max: hashers.len() });
+				}
+
+				for (idx, ((value, hasher), ty)) in keys.iter().zip(hashers).zip(key_tys).enumerate() {
+					let mut value_bytes = Vec::new();
+					value
+						.encode_as_type_to(ty, metadata.types(), &mut value_bytes)
+						.map_err(|e| StorageEncodeError::CouldNotEncodeKey { key: idx, encode_error: e })?;
+					hash_bytes(hasher, &value_bytes, &mut bytes);
+				}
+			}
+		}
+
+		Ok(bytes)
+	}
+
+	// Find the storage location corresponding to some prefix and name.
+	fn prefix_and_name_to_location(&self, prefix: &str, name: &str) -> Result<StorageLocation, StorageEncodeError> {
+		let entries =
+			self.entries_by_prefix.get(prefix).ok_or_else(|| StorageEncodeError::PrefixNotFound(prefix.to_owned()))?;
+		let entry_index =
+			entries.entry_by_name.get(name).ok_or_else(|| StorageEncodeError::NameNotFound(name.to_owned()))?;
+
+		Ok(StorageLocation { prefix_index: entries.index, entry_index: *entry_index })
+	}
+}
+
+/// Hash some SCALE encoded bytes using the hasher provided, appending the result to the output.
+fn hash_bytes(hasher: &FrameStorageHasher, bytes: &[u8], out: &mut Vec<u8>) {
+	match hasher {
+		FrameStorageHasher::Blake2_128 => out.extend(blake2_128(bytes)),
+		FrameStorageHasher::Blake2_256 => out.extend(blake2_256(bytes)),
+		FrameStorageHasher::Blake2_128Concat => {
+			out.extend(blake2_128(bytes));
+			out.extend(bytes);
+		}
+		FrameStorageHasher::Twox128 => out.extend(twox_128(bytes)),
+		FrameStorageHasher::Twox256 => out.extend(twox_256(bytes)),
+		FrameStorageHasher::Twox64Concat => {
+			out.extend(twox_64(bytes));
+			out.extend(bytes);
+		}
+		FrameStorageHasher::Identity => out.extend(bytes),
+	}
+}
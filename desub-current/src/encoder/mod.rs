@@ -0,0 +1,141 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Given some [`Metadata`] obtained from a substrate node, this module exposes the functionality to
+//! SCALE encode various values, such as storage keys, that are compatible with that metadata.
+//!
+//! See [`encode_storage()`] and then the documentation on [`StorageEncoder`] to build storage keys, or
+//! [`encode_value_by_id()`] and [`encode_call_data()`] to encode values and calls.
+
+mod encode_storage;
+
+use crate::metadata::Metadata;
+use crate::TypeId;
+use scale_encode::EncodeAsType;
+use scale_value::Value;
+
+// Re-export the EncodeValueError here, which we expose in our error enums.
+pub use scale_encode::Error as EncodeValueError;
+
+// Re-export storage related types that are part of our public interface.
+pub use encode_storage::{StorageEncodeError, StorageEncoder};
+
+/// Generate a [`StorageEncoder`] struct which is capable of building SCALE encoded storage keys. It's advisable
+/// to cache this struct if you are encoding lots of storage keys, since it is non-trivial to create.
+///
+/// # Example
+///
+/// ```rust
+/// use hex;
+/// use desub_current::{ Metadata, Value, encoder };
+///
+/// let metadata_scale_encoded = include_bytes!("../../tests/data/v14_metadata_polkadot.scale");
+/// let metadata = Metadata::from_bytes(metadata_scale_encoded).unwrap();
+///
+/// // With the help of our metadata, we can create a storage encoder:
+/// let storage_encoder = encoder::encode_storage(&metadata);
+///
+/// // Build the key for a lookup like `System.BlockHash(1000)`:
+/// let storage_key = storage_encoder
+///     .encode_key(&metadata, "System", "BlockHash", &[Value::u128(1000)])
+///     .expect("can encode storage key");
+///
+/// assert_eq!(
+///     hex::encode(storage_key),
+///     "26aa394eea5630e07c48ae0c9558cef7a44704b568d21667356a5a050c118746b6ff6f7d467b87a9e8030000"
+/// );
+/// ```
+pub fn encode_storage(metadata: &Metadata) -> StorageEncoder {
+	encode_storage::StorageEncoder::generate_from_metadata(metadata)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CallEncodeError {
+	#[error("Cannot find a pallet called '{0}' with calls")]
+	PalletNotFound(String),
+	#[error("Cannot find a call called '{call}' in the pallet '{pallet}'")]
+	CallNotFound { pallet: String, call: String },
+	#[error("Wrong number of arguments provided; expected {expected} but got {got}")]
+	WrongNumberOfArguments { expected: usize, got: usize },
+	#[error("Couldn't encode argument {arg}: {encode_error}")]
+	CouldNotEncodeArgument { arg: usize, encode_error: EncodeValueError },
+}
+
+/// SCALE encode a single [`Value`], given some metadata and the ID of the type that we are expecting it to
+/// encode into. An error is returned if the shape of the value does not line up with the type.
+pub fn encode_value_by_id<Id: Into<TypeId>, T>(
+	metadata: &Metadata,
+	ty: Id,
+	value: &Value<T>,
+) -> Result<Vec<u8>, EncodeValueError> {
+	value.encode_as_type(ty.into(), metadata.types())
+}
+
+/// SCALE encode call data given the name of a pallet and call, and a [`Value`] for each of the call
+/// arguments. The result takes the form `(u8, u8, arguments)`, and can be decoded with
+/// [`crate::decoder::decode_call_data()`].
+///
+/// # Example
+///
+/// ```rust
+/// use desub_current::{ Metadata, Value, encoder };
+///
+/// let metadata_scale_encoded = include_bytes!("../../tests/data/v14_metadata_polkadot.scale");
+/// let metadata = Metadata::from_bytes(metadata_scale_encoded).unwrap();
+///
+/// // Auctions.bid(1, 2, 3, 4, 5):
+/// let call_data = encoder::encode_call_data(
+///     &metadata,
+///     "Auctions",
+///     "bid",
+///     &[
+///         Value::unnamed_composite(vec![Value::u128(1)]),
+///         Value::u128(2),
+///         Value::u128(3),
+///         Value::u128(4),
+///         Value::u128(5),
+///     ],
+/// )
+/// .expect("can encode call data");
+///
+/// assert_eq!(hex::encode(call_data), "480104080c1014");
+/// ```
+pub fn encode_call_data<T>(
+	metadata: &Metadata,
+	pallet: &str,
+	call: &str,
+	args: &[Value<T>],
+) -> Result<Vec<u8>, CallEncodeError> {
+	let (pallet_index, variants) = metadata
+		.call_variants_by_pallet_name(pallet)
+		.ok_or_else(|| CallEncodeError::PalletNotFound(pallet.to_owned()))?;
+	let variant = variants
+		.iter()
+		.find(|v| v.name == call)
+		.ok_or_else(|| CallEncodeError::CallNotFound { pallet: pallet.to_owned(), call: call.to_owned() })?;
+
+	if args.len() != variant.fields.len() {
+		return Err(CallEncodeError::WrongNumberOfArguments { expected: variant.fields.len(), got: args.len() });
+	}
+
+	let mut bytes = vec![pallet_index, variant.index];
+	for (idx, (arg, field)) in args.iter().zip(&variant.fields).enumerate() {
+		arg.encode_as_type_to(field.ty.id, metadata.types(), &mut bytes)
+			.map_err(|e| CallEncodeError::CouldNotEncodeArgument { arg: idx, encode_error: e })?;
+	}
+
+	Ok(bytes)
+}
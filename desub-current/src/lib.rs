@@ -15,10 +15,13 @@
 // along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
 
 //! A crate to decode extrinsics, signer payloads and storage keys for substrate nodes using V14+ metadata.
-//! See [`decoder`] for more information.
+//! See [`decoder`] for more information, and [`encoder`] for going in the other direction.
+//! See [`verify`] to check the signatures of decoded extrinsics.
 
 pub mod decoder;
+pub mod encoder;
 pub mod metadata;
+pub mod verify;
 
 pub use metadata::Metadata;
 pub use scale_value::{Value, ValueDef};
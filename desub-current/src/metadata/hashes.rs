@@ -0,0 +1,145 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{type_hash::TypeHasher, Metadata, MetadataPallet, MetadataStorageEntry};
+use parity_scale_codec::Encode;
+use scale_info::{form::PortableForm, Variant};
+use sp_core::{blake2_256, H256};
+use std::collections::BTreeMap;
+
+/// The structural hashes of a single pallet and of each call, event and storage entry in it,
+/// as returned from [`Metadata::pallet_hashes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PalletHashes {
+	/// The name of the pallet.
+	pub name: String,
+	/// See [`MetadataPallet::hash`].
+	pub hash: H256,
+	/// The hash of each call, by name. See [`MetadataPallet::call_hash`].
+	pub calls: BTreeMap<String, H256>,
+	/// The hash of each event, by name. See [`MetadataPallet::event_hash`].
+	pub events: BTreeMap<String, H256>,
+	/// The hash of each storage entry, by name. See [`MetadataStorageEntry::hash`].
+	pub storage: BTreeMap<String, H256>,
+}
+
+impl Metadata {
+	/// The structural hashes of every pallet, and of each call, event and storage entry in them. These
+	/// are the same as the hashes handed back for each item individually, but the work of hashing the
+	/// types involved is shared, which makes this much quicker when hashing everything.
+	pub fn pallet_hashes(&self) -> Vec<PalletHashes> {
+		let mut hasher = TypeHasher::new(self.types());
+		self.pallets()
+			.map(|pallet| PalletHashes {
+				name: pallet.name().to_owned(),
+				hash: H256(pallet.hash_with(&mut hasher)),
+				calls: pallet.calls().iter().map(|c| (c.name.clone(), pallet.variant_hash(&mut hasher, c))).collect(),
+				events: pallet.events().iter().map(|e| (e.name.clone(), pallet.variant_hash(&mut hasher, e))).collect(),
+				storage: pallet.storage().map(|e| (e.name().to_owned(), H256(e.hash_with(&mut hasher)))).collect(),
+			})
+			.collect()
+	}
+}
+
+// Structural hashes are derived from the shape of the types involved rather than their IDs, and so
+// they can be compared across runtime versions to find out whether something decodes the same way.
+impl<'a> MetadataPallet<'a> {
+	/// A structural hash of this pallet, covering its name, its index, and the hashes of each of its
+	/// calls, events, errors and storage entries. If two runtime versions give the same hash for a
+	/// pallet, then anything belonging to it will decode identically in either.
+	///
+	/// Hashes don't depend on the IDs that types have in the registry, their paths or any docs.
+	pub fn hash(&self) -> H256 {
+		H256(self.hash_with(&mut TypeHasher::new(self.metadata.types())))
+	}
+
+	/// A structural hash of the call with the given name, covering its name, index and arguments, and
+	/// the name and index of this pallet (which decide how the call is encoded and decoded too).
+	/// Returns `None` if no such call exists.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use desub_current::Metadata;
+	///
+	/// let metadata_scale_encoded = include_bytes!("../../tests/data/v14_metadata_polkadot.scale");
+	/// let metadata = Metadata::from_bytes(metadata_scale_encoded).unwrap();
+	///
+	/// let balances = metadata.pallet("Balances").unwrap();
+	/// let transfer = balances.call_hash("transfer").unwrap();
+	/// assert_ne!(transfer, balances.call_hash("transfer_all").unwrap());
+	/// ```
+	pub fn call_hash(&self, name: &str) -> Option<H256> {
+		let call = self.call(name)?;
+		Some(self.variant_hash(&mut TypeHasher::new(self.metadata.types()), call))
+	}
+
+	/// A structural hash of the event with the given name, covering its name, index and fields, and
+	/// the name and index of this pallet. Returns `None` if no such event exists.
+	pub fn event_hash(&self, name: &str) -> Option<H256> {
+		let event = self.event(name)?;
+		Some(self.variant_hash(&mut TypeHasher::new(self.metadata.types()), event))
+	}
+
+	fn hash_with(&self, hasher: &mut TypeHasher) -> [u8; 32] {
+		let mut bytes = Vec::new();
+		self.name().encode_to(&mut bytes);
+		self.index().encode_to(&mut bytes);
+		for variants in [self.calls(), self.events(), self.errors()] {
+			(variants.len() as u32).encode_to(&mut bytes);
+			for variant in variants {
+				bytes.extend(hasher.hash_variant(variant));
+			}
+		}
+		let storage = self.storage().map(|entry| entry.hash_with(hasher)).collect::<Vec<_>>();
+		(storage.len() as u32).encode_to(&mut bytes);
+		for hash in storage {
+			bytes.extend(hash);
+		}
+		blake2_256(&bytes)
+	}
+
+	fn variant_hash(&self, hasher: &mut TypeHasher, variant: &Variant<PortableForm>) -> H256 {
+		let mut bytes = Vec::new();
+		self.name().encode_to(&mut bytes);
+		self.index().encode_to(&mut bytes);
+		bytes.extend(hasher.hash_variant(variant));
+		H256(blake2_256(&bytes))
+	}
+}
+
+impl<'a> MetadataStorageEntry<'a> {
+	/// A structural hash of this storage entry, covering its name, modifier, hashers, the types of
+	/// its keys and value, and its default value.
+	pub fn hash(&self) -> H256 {
+		H256(self.hash_with(&mut TypeHasher::new(self.metadata.types())))
+	}
+
+	fn hash_with(&self, hasher: &mut TypeHasher) -> [u8; 32] {
+		let mut bytes = Vec::new();
+		self.name().encode_to(&mut bytes);
+		self.modifier().encode_to(&mut bytes);
+		self.hashers().encode_to(&mut bytes);
+		let keys = self.key_tys();
+		(keys.len() as u32).encode_to(&mut bytes);
+		for key in keys {
+			bytes.extend(hasher.hash(key));
+		}
+		bytes.extend(hasher.hash(self.value_ty()));
+		self.default_bytes().encode_to(&mut bytes);
+		blake2_256(&bytes)
+	}
+}
@@ -17,14 +17,24 @@
 //! Decode SCALE encoded metadata from a substrate node into a format that
 //! we can make use of for decoding (see [`crate::decoder`]).
 
+mod hashes;
 mod readonly_array;
+mod shape;
+mod type_hash;
+mod type_signature;
 mod u8_map;
 mod version_14;
+mod version_15;
 
-use crate::{ScaleInfoTypeId, Type, TypeId};
+use crate::{ScaleInfoTypeId, Type, TypeId, Value};
 use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+
+// Re-export the storage entry details that are part of our public interface.
+pub use frame_metadata::v14::{StorageEntryModifier, StorageHasher};
+pub use hashes::PalletHashes;
 use parity_scale_codec::Decode;
 use readonly_array::ReadonlyArray;
+use scale_decode::DecodeAsType;
 use scale_info::{form::PortableForm, PortableRegistry};
 use u8_map::U8Map;
 
@@ -36,7 +46,7 @@ type SignedExtensionMetadata = frame_metadata::v14::SignedExtensionMetadata<Port
 type StorageEntryMetadata = frame_metadata::v14::StorageEntryMetadata<scale_info::form::PortableForm>;
 
 /// An enum of the possible errors that can be returned from attempting to construct
-/// a [`Metadata`] struct.
+/// a [`Metadata`] struct, or to decode the constants within it.
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum MetadataError {
 	#[error("metadata version {0} is not supported")]
@@ -47,6 +57,10 @@ pub enum MetadataError {
 	ExpectedVariantType { got: String },
 	#[error("could not find type with ID {0}")]
 	TypeNotFound(u32),
+	#[error("could not decode the value of constant {pallet}.{name}: {reason}")]
+	CannotDecodeConstant { pallet: String, name: String, reason: String },
+	#[error("constant {pallet}.{name} has {remaining} bytes left over after decoding its value")]
+	ExcessConstantBytes { pallet: String, name: String, remaining: usize },
 }
 
 /// This is a representation of the SCALE encoded metadata obtained from a substrate
@@ -54,16 +68,32 @@ pub enum MetadataError {
 /// to decode encoded extrinsics and storage keys.
 #[derive(Debug)]
 pub struct Metadata {
+	/// The version of the runtime metadata that this was built from.
+	version: u32,
 	/// Details about the extrinsic format.
 	extrinsic: MetadataExtrinsic,
+	/// Details about each pallet, in the order that they appear in the metadata.
+	pallets: ReadonlyArray<MetadataPalletDetails>,
 	/// Hash pallet calls by index, since when decoding, we'll have the pallet/call
 	/// `u8`'s available to us to look them up by.
 	pallet_calls_by_index: U8Map<MetadataPalletCalls>,
+	/// Hash pallet events by index, for the same reason as calls; decoded events
+	/// begin with the pallet/event `u8`'s.
+	pallet_events_by_index: U8Map<MetadataPalletEvents>,
 	/// Store storage entry information as a readonly array, allowing us to look up a
 	/// specific storage entry using a key like `(usize,usize)`. Since the order of
 	/// entries in this array is not guaranteed between metadata versions, it should
 	/// not be exposed.
 	pallet_storage: ReadonlyArray<MetadataPalletStorage>,
+	/// The constants exposed by each pallet, in the order that the pallets and
+	/// constants appear in the metadata.
+	pallet_constants: ReadonlyArray<MetadataConstant>,
+	/// The outer call, event and error enum types (V15+).
+	outer_enums: Option<MetadataOuterEnums>,
+	/// The runtime APIs exposed by the node (V15+).
+	runtime_apis: Vec<MetadataRuntimeApi>,
+	/// Custom values that the node chooses to expose (V15+).
+	custom_values: Vec<MetadataCustomValue>,
 	/// Type information lives inside this.
 	types: PortableRegistry,
 }
@@ -81,13 +111,33 @@ impl Metadata {
 	///     | xxd -r -p > node_metadata.scale
 	/// ```
 	///
-	/// This file can then be read and passed directly to this method.
+	/// This file can then be read and passed directly to this method. `state_getMetadata` always returns
+	/// V14 metadata; newer versions can be obtained by calling the `Metadata_metadata_at_version` runtime API.
+	/// That hands back the same bytes SCALE encoded as an `Option<Vec<u8>>`, and so they need unwrapping
+	/// before being passed here; see [`Metadata::from_metadata_at_version`].
 	pub fn from_bytes(bytes: &[u8]) -> Result<Self, MetadataError> {
 		log::trace!("Decoding metadata");
 		let meta = RuntimeMetadataPrefixed::decode(&mut &*bytes)?;
 		Self::from_runtime_metadata(meta.1)
 	}
 
+	/// Decode the output of the `Metadata_metadata_at_version` runtime API, which wraps the bytes expected by
+	/// [`Metadata::from_bytes`] in an `Option<Vec<u8>>`. Returns `Ok(None)` if the node doesn't support the
+	/// metadata version that was asked for.
+	///
+	/// ```sh
+	/// curl -sX POST -H "Content-Type: application/json" --data '{"jsonrpc":"2.0","method":"state_call", "params": ["Metadata_metadata_at_version", "0x0f000000"], "id": 1}' localhost:9933 \
+	///     | jq .result \
+	///     | cut -d '"' -f 2 \
+	///     | xxd -r -p > node_metadata_v15.scale
+	/// ```
+	pub fn from_metadata_at_version(bytes: &[u8]) -> Result<Option<Self>, MetadataError> {
+		let Some(bytes) = <Option<Vec<u8>>>::decode(&mut &*bytes)? else {
+			return Ok(None);
+		};
+		Self::from_bytes(&bytes).map(Some)
+	}
+
 	/// Convert the substrate runtime metadata into our Metadata.
 	pub fn from_runtime_metadata(metadata: RuntimeMetadata) -> Result<Self, MetadataError> {
 		match metadata {
@@ -95,27 +145,152 @@ impl Metadata {
 				log::trace!("V14 metadata found.");
 				version_14::decode(meta_v14)
 			}
+			RuntimeMetadata::V15(meta_v15) => {
+				log::trace!("V15 metadata found.");
+				version_15::decode(meta_v15)
+			}
+			// Newer versions are expected to get their own `version_N` module, which (like V15) can
+			// build on `version_14::decode_pallets` for anything that hasn't changed.
 			unsupported_meta => Err(MetadataError::UnsupportedVersion(unsupported_meta.version())),
 		}
 	}
 
+	/// The version of the runtime metadata that this was built from (14 or 15).
+	pub fn version(&self) -> u32 {
+		self.version
+	}
+
 	/// Return details about the type of extrinsic supported by this metadata.
 	pub fn extrinsic(&self) -> &MetadataExtrinsic {
 		&self.extrinsic
 	}
 
+	/// Return the outer enum types, or `None` if the metadata predates V15 and doesn't describe them.
+	pub fn outer_enums(&self) -> Option<&MetadataOuterEnums> {
+		self.outer_enums.as_ref()
+	}
+
+	/// Return the runtime API with the given name (for example `Core`), or `None` if no such API exists.
+	/// Metadata prior to V15 doesn't describe any runtime APIs.
+	pub fn runtime_api(&self, name: &str) -> Option<&MetadataRuntimeApi> {
+		self.runtime_apis.iter().find(|api| api.name == name)
+	}
+
+	/// Iterate over the runtime APIs described by the metadata.
+	pub fn runtime_apis(&self) -> impl Iterator<Item = &MetadataRuntimeApi> {
+		self.runtime_apis.iter()
+	}
+
+	/// Return the custom value with the given name, or `None` if no such value exists.
+	/// Metadata prior to V15 doesn't contain any custom values.
+	pub fn custom_value(&self, name: &str) -> Option<&MetadataCustomValue> {
+		self.custom_values.iter().find(|value| value.name == name)
+	}
+
+	/// Iterate over the custom values in the metadata.
+	pub fn custom_values(&self) -> impl Iterator<Item = &MetadataCustomValue> {
+		self.custom_values.iter()
+	}
+
+	/// Return the documentation for the given pallet, or `None` if there is no such pallet or it has no docs
+	/// (as is always the case for metadata prior to V15).
+	pub fn pallet_docs(&self, pallet: &str) -> Option<&[String]> {
+		self.pallet(pallet).map(|p| p.docs()).filter(|docs| !docs.is_empty())
+	}
+
+	/// Iterate over the pallets in the metadata, in the order that they are declared.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use desub_current::Metadata;
+	///
+	/// let metadata_scale_encoded = include_bytes!("../../tests/data/v14_metadata_polkadot.scale");
+	/// let metadata = Metadata::from_bytes(metadata_scale_encoded).unwrap();
+	///
+	/// // List the callable functions in each pallet:
+	/// for pallet in metadata.pallets() {
+	///     for call in pallet.calls() {
+	///         let args: Vec<_> = call.fields.iter().filter_map(|f| f.name.as_deref()).collect();
+	///         println!("{}.{}({})", pallet.name(), call.name, args.join(", "));
+	///     }
+	/// }
+	///
+	/// let balances = metadata.pallet("Balances").unwrap();
+	/// assert_eq!(balances.index(), 5);
+	/// assert!(balances.call("transfer").is_some());
+	/// ```
+	pub fn pallets(&self) -> impl Iterator<Item = MetadataPallet<'_>> {
+		self.pallets.iter().map(move |details| MetadataPallet { metadata: self, details })
+	}
+
+	/// Return the pallet with the given name, or `None` if no such pallet exists.
+	pub fn pallet(&self, name: &str) -> Option<MetadataPallet<'_>> {
+		self.pallets().find(|p| p.name() == name)
+	}
+
+	/// Return the pallet with the given index, or `None` if no such pallet exists.
+	pub fn pallet_by_index(&self, index: u8) -> Option<MetadataPallet<'_>> {
+		self.pallets().find(|p| p.index() == index)
+	}
+
+	/// Return the constant with the given name from the given pallet, or `None` if no such constant exists.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use desub_current::{ Metadata, Value };
+	///
+	/// let metadata_scale_encoded = include_bytes!("../../tests/data/v14_metadata_polkadot.scale");
+	/// let metadata = Metadata::from_bytes(metadata_scale_encoded).unwrap();
+	///
+	/// let existential_deposit = metadata.constant("Balances", "ExistentialDeposit").unwrap();
+	/// let value = existential_deposit.value(&metadata).unwrap();
+	/// assert_eq!(value.remove_context(), Value::u128(10_000_000_000));
+	/// ```
+	pub fn constant(&self, pallet: &str, name: &str) -> Option<&MetadataConstant> {
+		self.pallet_constants.iter().find(|c| c.pallet == pallet && c.name == name)
+	}
+
+	/// Iterate over every constant in the metadata, in the order that they are declared.
+	pub fn constants(&self) -> impl Iterator<Item = &MetadataConstant> {
+		self.pallet_constants.iter()
+	}
+
 	/// Given a [`crate::TypeId`], return the corresponding type from the type registry, if possible.
 	pub fn resolve<Id: Into<TypeId>>(&self, id: Id) -> Option<&Type> {
 		self.types.resolve(id.into())
 	}
 
+	/// Render the type with the given [`crate::TypeId`] as a Rust-like signature, such as
+	/// `Vec<(AccountId32, Compact<u128>)>`, or return `None` if the type cannot be found.
+	///
+	/// Named types are shortened to the last segment of their path, and are shown with any generic
+	/// parameters that the registry describes.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use desub_current::Metadata;
+	///
+	/// let metadata_scale_encoded = include_bytes!("../../tests/data/v14_metadata_polkadot.scale");
+	/// let metadata = Metadata::from_bytes(metadata_scale_encoded).unwrap();
+	///
+	/// let transfer = metadata.pallet("Balances").unwrap().call("transfer").unwrap();
+	/// let arg_types: Vec<_> = transfer.fields.iter().map(|f| metadata.type_signature(f.ty.id).unwrap()).collect();
+	/// assert_eq!(arg_types, ["MultiAddress<AccountId32, ()>", "Compact<u128>"]);
+	/// ```
+	pub fn type_signature<Id: Into<TypeId>>(&self, id: Id) -> Option<String> {
+		type_signature::type_signature(&self.types, id.into())
+	}
+
 	/// Return a reference to the [`scale_info`] type registry.
-	pub(crate) fn types(&self) -> &PortableRegistry {
+	pub fn types(&self) -> &PortableRegistry {
 		&self.types
 	}
 
 	/// Retrieve the storage entry at the location provided. Locations are generated from
-	/// [`crate::decoder::StorageDecoder`] calls, and should always exist. It is a user error
+	/// [`crate::decoder::StorageDecoder`] and [`crate::encoder::StorageEncoder`] calls, and should always exist. It is a user error
 	/// to use a different [`Metadata`] instance for obtaining these locations from the instance
 	/// used to retrieve storage entry details from them.
 	pub(crate) fn storage_entry(&self, loc: StorageLocation) -> StorageEntry<'_> {
@@ -134,6 +309,20 @@ impl Metadata {
 		self.pallet_storage.iter()
 	}
 
+	/// Metadata info for storage maps contains a vec of hashers for each key type, and a type
+	/// representing the key(s). Multiple hashers mean that the key is a tuple with one item per
+	/// hasher, but a map with a single hasher may also be keyed by a tuple, so we only split
+	/// the key into its fields in the former case.
+	///
+	/// See <https://github.com/paritytech/subxt/blob/793c945fbd2de022f523c39a84ee02609ba423a9/codegen/src/api/storage.rs#L105>
+	/// for another example of this being handled in code.
+	pub(crate) fn storage_map_key_tys(&self, hashers: &[StorageHasher], key: ScaleInfoTypeId) -> Vec<TypeId> {
+		match self.resolve(key.id).map(|ty| &ty.type_def) {
+			Some(scale_info::TypeDef::Tuple(tuple)) if hashers.len() > 1 => tuple.fields.iter().map(|f| f.id).collect(),
+			_ => vec![key.id],
+		}
+	}
+
 	/// Given the `u8` variant index of a pallet and call, this returns the pallet name and the call Variant
 	/// if found, or `None` if no such call exists at those indexes, or we don't have suitable call data.
 	pub(crate) fn call_variant_by_enum_index(
@@ -151,6 +340,35 @@ impl Metadata {
 		})
 	}
 
+	/// Given the name of a pallet, this returns the `u8` index of the pallet and the Variants describing each
+	/// of its calls, or `None` if no such pallet exists or it has no calls.
+	pub(crate) fn call_variants_by_pallet_name(
+		&self,
+		pallet: &str,
+	) -> Option<(u8, &[scale_info::Variant<PortableForm>])> {
+		let (pallet_index, p) = self.pallet_calls_by_index.iter().find(|(_, p)| p.name == pallet)?;
+		let calls = p.calls.as_ref()?;
+		let type_def_variant = self.get_variant(calls.calls_type_id)?;
+		Some((pallet_index, &type_def_variant.variants))
+	}
+
+	/// Given the `u8` variant index of a pallet and event, this returns the pallet name and the event Variant
+	/// if found, or `None` if no such event exists at those indexes, or we don't have suitable event data.
+	pub(crate) fn event_variant_by_enum_index(
+		&self,
+		pallet: u8,
+		event: u8,
+	) -> Option<(&str, &scale_info::Variant<PortableForm>)> {
+		self.pallet_events_by_index.get(pallet).and_then(|p| {
+			p.events.as_ref().and_then(|events| {
+				let type_def_variant = self.get_variant(events.events_type_id)?;
+				let index = *events.event_variant_indexes.get(event)?;
+				let variant = type_def_variant.variants.get(index)?;
+				Some((&*p.name, variant))
+			})
+		})
+	}
+
 	/// A helper function to get hold of a Variant given a type ID, or None if it's not found.
 	fn get_variant(&self, ty: ScaleInfoTypeId) -> Option<&TypeDefVariant> {
 		self.types.resolve(ty.id).and_then(|ty| match &ty.type_def {
@@ -199,11 +417,33 @@ struct MetadataCalls {
 	call_variant_indexes: U8Map<usize>,
 }
 
+#[derive(Debug)]
+struct MetadataPalletEvents {
+	/// The pallet name.
+	name: String,
+	/// Metadata may not contain event information. If it does,
+	/// it'll be here.
+	events: Option<MetadataEvents>,
+}
+
+#[derive(Debug)]
+struct MetadataEvents {
+	/// This allows us to find the type information corresponding to
+	/// the event in the [`PortableRegistry`].
+	events_type_id: ScaleInfoTypeId,
+	/// This allows us to map a u8 enum index to the correct event variant
+	/// from the events type, above.
+	event_variant_indexes: U8Map<usize>,
+}
+
 /// Information about the extrinsic format supported on the substrate node
 /// that the metadata was obtained from.
 #[derive(Debug, Clone)]
 pub struct MetadataExtrinsic {
 	version: u8,
+	address: ExtrinsicAddressType,
+	signature: ExtrinsicSignatureType,
+	call_type_id: Option<TypeId>,
 	signed_extensions: Vec<SignedExtensionMetadata>,
 }
 
@@ -214,6 +454,37 @@ impl MetadataExtrinsic {
 		self.version
 	}
 
+	/// The ID of the address type used in signed extrinsics, if the metadata describes it.
+	pub fn address_type_id(&self) -> Option<TypeId> {
+		match self.address {
+			ExtrinsicAddressType::MultiAddress { type_id, .. } => type_id,
+			ExtrinsicAddressType::Other(type_id) => Some(type_id),
+		}
+	}
+
+	/// The ID of the signature type used in signed extrinsics, if the metadata describes it.
+	pub fn signature_type_id(&self) -> Option<TypeId> {
+		match self.signature {
+			ExtrinsicSignatureType::MultiSignature { type_id } => type_id,
+			ExtrinsicSignatureType::Other(type_id) => Some(type_id),
+		}
+	}
+
+	/// The ID of the runtime call type (ie the enum of all pallet calls), if the metadata describes it.
+	pub fn call_type_id(&self) -> Option<TypeId> {
+		self.call_type_id
+	}
+
+	/// How the address in signed extrinsics should be decoded.
+	pub(crate) fn address(&self) -> ExtrinsicAddressType {
+		self.address
+	}
+
+	/// How the signature in signed extrinsics should be decoded.
+	pub(crate) fn signature(&self) -> ExtrinsicSignatureType {
+		self.signature
+	}
+
 	/// Part of the extrinsic signature area can be varied to include whatever information
 	/// a node decides is important. This returns details about that part.
 	pub(crate) fn signed_extensions(&self) -> &[SignedExtensionMetadata] {
@@ -221,6 +492,395 @@ impl MetadataExtrinsic {
 	}
 }
 
+/// The address type used in signed extrinsics. The standard `MultiAddress` can be decoded directly, but
+/// anything else is decoded into a [`Value`] using the type information in the metadata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExtrinsicAddressType {
+	/// `MultiAddress<AccountId32, AccountIndex>`, where the account index is either a `u32` or `()`.
+	/// The type ID may be missing if the metadata doesn't describe the extrinsic type parameters.
+	MultiAddress { type_id: Option<TypeId>, unit_index: bool },
+	/// Some other address type.
+	Other(TypeId),
+}
+
+/// The signature type used in signed extrinsics. The standard `MultiSignature` can be decoded directly, but
+/// anything else is decoded into a [`Value`] using the type information in the metadata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExtrinsicSignatureType {
+	/// `MultiSignature`. The type ID may be missing if the metadata doesn't describe the extrinsic
+	/// type parameters.
+	MultiSignature { type_id: Option<TypeId> },
+	/// Some other signature type.
+	Other(TypeId),
+}
+
+/// A constant exposed by some pallet, along with its SCALE encoded value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataConstant {
+	pallet: String,
+	name: String,
+	ty: TypeId,
+	bytes: Vec<u8>,
+	docs: Vec<String>,
+}
+
+impl MetadataConstant {
+	/// The name of the pallet that this constant belongs to.
+	pub fn pallet(&self) -> &str {
+		&self.pallet
+	}
+
+	/// The name of the constant.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// The ID of the constant's type, which can be handed to [`Metadata::resolve`].
+	pub fn ty(&self) -> TypeId {
+		self.ty
+	}
+
+	/// The SCALE encoded value of the constant.
+	pub fn bytes(&self) -> &[u8] {
+		&self.bytes
+	}
+
+	/// Decode the value of the constant, given the [`Metadata`] that it came from. All of the
+	/// encoded bytes are expected to be consumed.
+	pub fn value(&self, metadata: &Metadata) -> Result<Value<TypeId>, MetadataError> {
+		let cursor = &mut &*self.bytes;
+		let value = Value::decode_as_type(cursor, self.ty, metadata.types()).map_err(|e| {
+			MetadataError::CannotDecodeConstant {
+				pallet: self.pallet.clone(),
+				name: self.name.clone(),
+				reason: e.to_string(),
+			}
+		})?;
+		if !cursor.is_empty() {
+			return Err(MetadataError::ExcessConstantBytes {
+				pallet: self.pallet.clone(),
+				name: self.name.clone(),
+				remaining: cursor.len(),
+			});
+		}
+		Ok(value)
+	}
+
+	/// Documentation for the constant.
+	pub fn docs(&self) -> &[String] {
+		&self.docs
+	}
+}
+
+/// The types of the outer enums, which aggregate the calls, events and errors of every pallet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetadataOuterEnums {
+	call_enum_ty: TypeId,
+	event_enum_ty: TypeId,
+	error_enum_ty: TypeId,
+}
+
+impl MetadataOuterEnums {
+	/// The ID of the runtime call type.
+	pub fn call_enum_ty(&self) -> TypeId {
+		self.call_enum_ty
+	}
+
+	/// The ID of the runtime event type.
+	pub fn event_enum_ty(&self) -> TypeId {
+		self.event_enum_ty
+	}
+
+	/// The ID of the runtime error type.
+	pub fn error_enum_ty(&self) -> TypeId {
+		self.error_enum_ty
+	}
+}
+
+/// A runtime API exposed by the node, such as `Core` or `Metadata`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataRuntimeApi {
+	name: String,
+	methods: Vec<MetadataRuntimeApiMethod>,
+	docs: Vec<String>,
+}
+
+impl MetadataRuntimeApi {
+	/// The name of the runtime API.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Return the method with the given name, or `None` if no such method exists.
+	pub fn method(&self, name: &str) -> Option<&MetadataRuntimeApiMethod> {
+		self.methods.iter().find(|m| m.name == name)
+	}
+
+	/// The methods that make up the runtime API.
+	pub fn methods(&self) -> &[MetadataRuntimeApiMethod] {
+		&self.methods
+	}
+
+	/// Documentation for the runtime API.
+	pub fn docs(&self) -> &[String] {
+		&self.docs
+	}
+}
+
+/// A method belonging to some [`MetadataRuntimeApi`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataRuntimeApiMethod {
+	name: String,
+	inputs: Vec<MetadataRuntimeApiParam>,
+	output: TypeId,
+	docs: Vec<String>,
+}
+
+impl MetadataRuntimeApiMethod {
+	/// The name of the method.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// The parameters that the method takes, in order.
+	pub fn inputs(&self) -> &[MetadataRuntimeApiParam] {
+		&self.inputs
+	}
+
+	/// The ID of the type that the method returns.
+	pub fn output(&self) -> TypeId {
+		self.output
+	}
+
+	/// Documentation for the method.
+	pub fn docs(&self) -> &[String] {
+		&self.docs
+	}
+}
+
+/// A parameter of some [`MetadataRuntimeApiMethod`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataRuntimeApiParam {
+	name: String,
+	ty: TypeId,
+}
+
+impl MetadataRuntimeApiParam {
+	/// The name of the parameter.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// The ID of the parameter's type.
+	pub fn ty(&self) -> TypeId {
+		self.ty
+	}
+}
+
+/// A custom value that the node exposes in its metadata. The value is left SCALE encoded, and can be
+/// decoded with [`crate::decoder::decode_value_by_id`] given its [`MetadataCustomValue::ty`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataCustomValue {
+	name: String,
+	ty: TypeId,
+	bytes: Vec<u8>,
+}
+
+impl MetadataCustomValue {
+	/// The name of the custom value.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// The ID of the custom value's type.
+	pub fn ty(&self) -> TypeId {
+		self.ty
+	}
+
+	/// The SCALE encoded custom value.
+	pub fn bytes(&self) -> &[u8] {
+		&self.bytes
+	}
+}
+
+// The details we keep about each pallet, so that they can be introspected via [`MetadataPallet`].
+#[derive(Debug)]
+struct MetadataPalletDetails {
+	name: String,
+	index: u8,
+	calls_type_id: Option<TypeId>,
+	events_type_id: Option<TypeId>,
+	errors_type_id: Option<TypeId>,
+	/// The index of this pallet's storage in [`Metadata::pallet_storage`], if it has any.
+	storage_index: Option<usize>,
+	docs: Vec<String>,
+}
+
+/// A read-only view of a single pallet in the metadata, obtained from [`Metadata::pallets`] or
+/// [`Metadata::pallet`]. Calls, events and errors are described by [`scale_info::Variant`]s, which
+/// hold the name, index, fields and docs of each.
+#[derive(Debug, Clone, Copy)]
+pub struct MetadataPallet<'a> {
+	metadata: &'a Metadata,
+	details: &'a MetadataPalletDetails,
+}
+
+impl<'a> MetadataPallet<'a> {
+	/// The name of the pallet.
+	pub fn name(&self) -> &'a str {
+		&self.details.name
+	}
+
+	/// The index of the pallet, which is the first byte of its encoded calls and events.
+	pub fn index(&self) -> u8 {
+		self.details.index
+	}
+
+	/// Documentation for the pallet. This is always empty for metadata prior to V15.
+	pub fn docs(&self) -> &'a [String] {
+		&self.details.docs
+	}
+
+	/// The ID of the type describing the pallet calls, if it has any.
+	pub fn calls_type_id(&self) -> Option<TypeId> {
+		self.details.calls_type_id
+	}
+
+	/// The calls in the pallet.
+	pub fn calls(&self) -> &'a [scale_info::Variant<PortableForm>] {
+		self.variants(self.details.calls_type_id)
+	}
+
+	/// Return the call with the given name, or `None` if no such call exists.
+	pub fn call(&self, name: &str) -> Option<&'a scale_info::Variant<PortableForm>> {
+		self.calls().iter().find(|v| v.name == name)
+	}
+
+	/// The ID of the type describing the pallet events, if it has any.
+	pub fn events_type_id(&self) -> Option<TypeId> {
+		self.details.events_type_id
+	}
+
+	/// The events that the pallet can emit.
+	pub fn events(&self) -> &'a [scale_info::Variant<PortableForm>] {
+		self.variants(self.details.events_type_id)
+	}
+
+	/// Return the event with the given name, or `None` if no such event exists.
+	pub fn event(&self, name: &str) -> Option<&'a scale_info::Variant<PortableForm>> {
+		self.events().iter().find(|v| v.name == name)
+	}
+
+	/// The ID of the type describing the pallet errors, if it has any.
+	pub fn errors_type_id(&self) -> Option<TypeId> {
+		self.details.errors_type_id
+	}
+
+	/// The errors that the pallet can return.
+	pub fn errors(&self) -> &'a [scale_info::Variant<PortableForm>] {
+		self.variants(self.details.errors_type_id)
+	}
+
+	/// Return the error with the given name, or `None` if no such error exists.
+	pub fn error(&self, name: &str) -> Option<&'a scale_info::Variant<PortableForm>> {
+		self.errors().iter().find(|v| v.name == name)
+	}
+
+	/// The storage entries in the pallet.
+	pub fn storage(&self) -> impl Iterator<Item = MetadataStorageEntry<'a>> {
+		let metadata = self.metadata;
+		let storage = self.details.storage_index.and_then(|idx| metadata.pallet_storage.get(idx));
+		storage.into_iter().flat_map(move |storage| {
+			storage.entries().map(move |entry| MetadataStorageEntry { metadata, prefix: &storage.prefix, entry })
+		})
+	}
+
+	/// Return the storage entry with the given name, or `None` if no such entry exists.
+	pub fn storage_entry(&self, name: &str) -> Option<MetadataStorageEntry<'a>> {
+		self.storage().find(|entry| entry.name() == name)
+	}
+
+	/// The constants in the pallet.
+	pub fn constants(&self) -> impl Iterator<Item = &'a MetadataConstant> {
+		let name = self.name();
+		self.metadata.constants().filter(move |c| c.pallet == name)
+	}
+
+	/// Return the constant with the given name, or `None` if no such constant exists.
+	pub fn constant(&self, name: &str) -> Option<&'a MetadataConstant> {
+		self.metadata.constant(self.name(), name)
+	}
+
+	fn variants(&self, type_id: Option<TypeId>) -> &'a [scale_info::Variant<PortableForm>] {
+		let ty = type_id.and_then(|id| self.metadata.resolve(id));
+		match ty.map(|ty| &ty.type_def) {
+			Some(scale_info::TypeDef::Variant(variant)) => &variant.variants,
+			_ => &[],
+		}
+	}
+}
+
+/// A read-only view of a single storage entry, obtained from [`MetadataPallet::storage`].
+#[derive(Debug, Clone, Copy)]
+pub struct MetadataStorageEntry<'a> {
+	metadata: &'a Metadata,
+	prefix: &'a str,
+	entry: &'a StorageEntryMetadata,
+}
+
+impl<'a> MetadataStorageEntry<'a> {
+	/// The storage prefix (normally the same as the pallet name) that the entry lives under.
+	pub fn prefix(&self) -> &'a str {
+		self.prefix
+	}
+
+	/// The name of the storage entry.
+	pub fn name(&self) -> &'a str {
+		&self.entry.name
+	}
+
+	/// Whether the entry returns an `Option` or a default value when nothing is stored.
+	pub fn modifier(&self) -> StorageEntryModifier {
+		self.entry.modifier.clone()
+	}
+
+	/// The hashers used for each of the map keys. This is empty for plain storage entries.
+	pub fn hashers(&self) -> &'a [StorageHasher] {
+		match &self.entry.ty {
+			frame_metadata::v14::StorageEntryType::Plain(_) => &[],
+			frame_metadata::v14::StorageEntryType::Map { hashers, .. } => hashers,
+		}
+	}
+
+	/// The ID of the type of each map key, in the same order as [`MetadataStorageEntry::hashers`].
+	/// This is empty for plain storage entries.
+	pub fn key_tys(&self) -> Vec<TypeId> {
+		let frame_metadata::v14::StorageEntryType::Map { hashers, key, .. } = &self.entry.ty else {
+			return Vec::new();
+		};
+		self.metadata.storage_map_key_tys(hashers, *key)
+	}
+
+	/// The ID of the type of the values stored in this entry.
+	pub fn value_ty(&self) -> TypeId {
+		match &self.entry.ty {
+			frame_metadata::v14::StorageEntryType::Plain(value) => value.id,
+			frame_metadata::v14::StorageEntryType::Map { value, .. } => value.id,
+		}
+	}
+
+	/// The SCALE encoded value returned when nothing is stored at some location, which can be decoded
+	/// with [`crate::decoder::decode_value_by_id`] given [`MetadataStorageEntry::value_ty`].
+	pub fn default_bytes(&self) -> &'a [u8] {
+		&self.entry.default
+	}
+
+	/// Documentation for the storage entry.
+	pub fn docs(&self) -> &'a [String] {
+		&self.entry.docs
+	}
+}
+
 /// An opaque struct that can be used to obtain details for a specific
 /// storage entry via [`Metadata::storage_entry`]. Used internally by
 /// our storage decoder.
@@ -0,0 +1,74 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{type_hash::TypeHasher, Metadata};
+use crate::TypeId;
+use desub_common::diff::{self, MetadataDiff, MetadataShape, PalletShape, StorageShape, TypeShape, VariantShape};
+use scale_info::{form::PortableForm, Variant};
+
+impl Metadata {
+	/// Describe the parts of this metadata which affect decoding, in a form that can be compared against
+	/// other versions of metadata. Types are compared by their structure, and not by their IDs in the type
+	/// registry. Legacy metadata has no type structure to compare against, and so only the layout of
+	/// fields is compared with it; see [`desub_common::diff`].
+	pub fn shape(&self) -> MetadataShape {
+		let mut hasher = TypeHasher::new(self.types());
+		let pallets = self
+			.pallets()
+			.map(|pallet| PalletShape {
+				name: pallet.name().to_owned(),
+				index: pallet.index(),
+				calls: self.variant_shapes(&mut hasher, pallet.calls()),
+				events: self.variant_shapes(&mut hasher, pallet.events()),
+				errors: self.variant_shapes(&mut hasher, pallet.errors()),
+				storage: pallet
+					.storage()
+					.map(|entry| StorageShape {
+						name: entry.name().to_owned(),
+						hashers: entry.hashers().iter().map(|h| format!("{h:?}")).collect(),
+						keys: entry.key_tys().into_iter().map(|ty| self.type_shape(&mut hasher, None, ty)).collect(),
+						value: self.type_shape(&mut hasher, None, entry.value_ty()),
+					})
+					.collect(),
+			})
+			.collect();
+		MetadataShape { pallets }
+	}
+
+	/// Compare this metadata with some newer version of it. See [`desub_common::diff`] for more.
+	pub fn diff(&self, newer: &Metadata) -> MetadataDiff {
+		diff::diff(&self.shape(), &newer.shape())
+	}
+
+	fn variant_shapes(&self, hasher: &mut TypeHasher, variants: &[Variant<PortableForm>]) -> Vec<VariantShape> {
+		variants
+			.iter()
+			.map(|v| VariantShape {
+				name: v.name.clone(),
+				index: v.index,
+				fields: v.fields.iter().map(|f| self.type_shape(hasher, f.name.clone(), f.ty.id)).collect(),
+			})
+			.collect()
+	}
+
+	fn type_shape(&self, hasher: &mut TypeHasher, name: Option<String>, ty: TypeId) -> TypeShape {
+		TypeShape {
+			name,
+			ty: self.type_signature(ty).unwrap_or_else(|| ty.to_string()),
+			structure: Some(hex::encode(hasher.hash(ty))),
+		}
+	}
+}
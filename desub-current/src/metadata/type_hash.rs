@@ -0,0 +1,194 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::TypeId;
+use parity_scale_codec::Encode;
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive, Variant};
+use sp_core::blake2_256;
+use std::collections::HashMap;
+
+// Tags which prefix the description of each kind of type before it is hashed.
+const COMPOSITE: u8 = 0;
+const VARIANT: u8 = 1;
+const SEQUENCE: u8 = 2;
+const ARRAY: u8 = 3;
+const TUPLE: u8 = 4;
+const PRIMITIVE: u8 = 5;
+const COMPACT: u8 = 6;
+const BIT_SEQUENCE: u8 = 7;
+const RECURSIVE: u8 = 8;
+const UNKNOWN: u8 = 9;
+
+/// Hashes the structure of types in a registry. Two types with the same hash are SCALE encoded
+/// identically and decode to the same shaped values, regardless of their IDs, paths or docs.
+pub(crate) struct TypeHasher<'a> {
+	types: &'a PortableRegistry,
+	// Each hash, along with any recursive types that it was worked out from. See `hash_type`.
+	cache: HashMap<TypeId, ([u8; 32], Vec<TypeId>)>,
+	// The types currently being hashed, so that recursive types can be spotted.
+	stack: Vec<TypeId>,
+}
+
+impl<'a> TypeHasher<'a> {
+	pub fn new(types: &'a PortableRegistry) -> Self {
+		TypeHasher { types, cache: HashMap::new(), stack: Vec::new() }
+	}
+
+	/// Return the structural hash of the type with the given ID.
+	pub fn hash(&mut self, id: TypeId) -> [u8; 32] {
+		self.hash_type(id).0
+	}
+
+	/// Return the structural hash of a single enum variant, such as a call or event. This
+	/// covers its name, its index and the name and type of each field.
+	pub fn hash_variant(&mut self, variant: &Variant<PortableForm>) -> [u8; 32] {
+		let mut bytes = Vec::new();
+		variant.name.encode_to(&mut bytes);
+		variant.index.encode_to(&mut bytes);
+		self.encode_fields(&variant.fields, &mut bytes, &mut |hasher: &mut Self, id| hasher.hash(id));
+		blake2_256(&bytes)
+	}
+
+	// Hash a type, also returning the lowest position in the stack that it (or any type it contains)
+	// refers back to, and the recursive types that were involved. A hash can only be cached if it
+	// doesn't depend on types further up the stack.
+	//
+	// A recursive type is hashed by following it until it refers back to a type that's already being
+	// hashed, and so its hash depends on where the cycle was entered. Cached hashes are therefore only
+	// reused when none of the recursive types that they were worked out from are being hashed, so that
+	// the result is the same no matter what was hashed beforehand.
+	fn hash_type(&mut self, id: TypeId) -> ([u8; 32], usize, Vec<TypeId>) {
+		if let Some((hash, recursive)) = self.cache.get(&id) {
+			if !recursive.iter().any(|r| self.stack.contains(r)) {
+				return (*hash, usize::MAX, recursive.clone());
+			}
+		}
+		// Recursive references are described by how far back up the stack they point, so that
+		// the hash doesn't depend on the ID of the type being referred to.
+		if let Some(pos) = self.stack.iter().position(|&i| i == id) {
+			let distance = (self.stack.len() - pos) as u32;
+			return (blake2_256(&(RECURSIVE, distance).encode()), pos, self.stack[pos..].to_vec());
+		}
+		let Some(ty) = self.types.resolve(id) else {
+			return (blake2_256(&[UNKNOWN]), usize::MAX, Vec::new());
+		};
+
+		let depth = self.stack.len();
+		self.stack.push(id);
+		let mut lowest = usize::MAX;
+		let mut recursive = Vec::new();
+		let mut child = |hasher: &mut Self, id: TypeId| {
+			let (hash, pos, child_recursive) = hasher.hash_type(id);
+			lowest = lowest.min(pos);
+			for r in child_recursive {
+				if !recursive.contains(&r) {
+					recursive.push(r);
+				}
+			}
+			hash
+		};
+
+		let mut bytes = Vec::new();
+		match &ty.type_def {
+			TypeDef::Composite(composite) => {
+				bytes.push(COMPOSITE);
+				self.encode_fields(&composite.fields, &mut bytes, &mut child);
+			}
+			TypeDef::Variant(variant) => {
+				bytes.push(VARIANT);
+				(variant.variants.len() as u32).encode_to(&mut bytes);
+				for v in &variant.variants {
+					v.name.encode_to(&mut bytes);
+					v.index.encode_to(&mut bytes);
+					self.encode_fields(&v.fields, &mut bytes, &mut child);
+				}
+			}
+			TypeDef::Sequence(seq) => {
+				bytes.push(SEQUENCE);
+				bytes.extend(child(self, seq.type_param.id));
+			}
+			TypeDef::Array(arr) => {
+				bytes.push(ARRAY);
+				arr.len.encode_to(&mut bytes);
+				bytes.extend(child(self, arr.type_param.id));
+			}
+			TypeDef::Tuple(tuple) => {
+				bytes.push(TUPLE);
+				(tuple.fields.len() as u32).encode_to(&mut bytes);
+				for field in &tuple.fields {
+					bytes.extend(child(self, field.id));
+				}
+			}
+			TypeDef::Primitive(primitive) => {
+				bytes.push(PRIMITIVE);
+				bytes.push(primitive_index(primitive));
+			}
+			TypeDef::Compact(compact) => {
+				bytes.push(COMPACT);
+				bytes.extend(child(self, compact.type_param.id));
+			}
+			TypeDef::BitSequence(bits) => {
+				// The bit order types are empty structs, so the only thing that
+				// distinguishes `Lsb0` from `Msb0` is their name.
+				bytes.push(BIT_SEQUENCE);
+				bytes.extend(child(self, bits.bit_store_type.id));
+				let order = self.types.resolve(bits.bit_order_type.id).and_then(|ty| ty.path.segments.last());
+				order.encode_to(&mut bytes);
+			}
+		}
+		self.stack.pop();
+
+		let hash = blake2_256(&bytes);
+		if lowest >= depth {
+			self.cache.insert(id, (hash, recursive.clone()));
+			lowest = usize::MAX;
+		}
+		(hash, lowest, recursive)
+	}
+
+	fn encode_fields(
+		&mut self,
+		fields: &[Field<PortableForm>],
+		bytes: &mut Vec<u8>,
+		child: &mut impl FnMut(&mut Self, TypeId) -> [u8; 32],
+	) {
+		(fields.len() as u32).encode_to(bytes);
+		for field in fields {
+			field.name.encode_to(bytes);
+			bytes.extend(child(self, field.ty.id));
+		}
+	}
+}
+
+fn primitive_index(primitive: &TypeDefPrimitive) -> u8 {
+	match primitive {
+		TypeDefPrimitive::Bool => 0,
+		TypeDefPrimitive::Char => 1,
+		TypeDefPrimitive::Str => 2,
+		TypeDefPrimitive::U8 => 3,
+		TypeDefPrimitive::U16 => 4,
+		TypeDefPrimitive::U32 => 5,
+		TypeDefPrimitive::U64 => 6,
+		TypeDefPrimitive::U128 => 7,
+		TypeDefPrimitive::U256 => 8,
+		TypeDefPrimitive::I8 => 9,
+		TypeDefPrimitive::I16 => 10,
+		TypeDefPrimitive::I32 => 11,
+		TypeDefPrimitive::I64 => 12,
+		TypeDefPrimitive::I128 => 13,
+		TypeDefPrimitive::I256 => 14,
+	}
+}
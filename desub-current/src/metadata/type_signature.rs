@@ -0,0 +1,114 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::TypeId;
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+
+/// Render the type with the given ID as a Rust-like signature, like `Vec<(AccountId32, Compact<u128>)>`.
+/// Named types are shortened to the last segment of their path, and only the generic parameters
+/// which are described in the registry are shown. Returns `None` if any type involved cannot be found.
+pub(super) fn type_signature(types: &PortableRegistry, id: TypeId) -> Option<String> {
+	let ty = types.resolve(id)?;
+
+	if let Some(name) = ty.path.segments.last() {
+		let params = ty
+			.type_params
+			.iter()
+			.filter_map(|param| param.ty)
+			.map(|param| type_signature(types, param.id))
+			.collect::<Option<Vec<_>>>()?;
+		return Some(with_params(name, &params));
+	}
+
+	let signature = match &ty.type_def {
+		TypeDef::Composite(composite) => fields_signature(types, &composite.fields)?,
+		TypeDef::Variant(variant) => {
+			let variants = variant
+				.variants
+				.iter()
+				.map(|v| Some(format!("{}{}", v.name, fields_signature(types, &v.fields)?)))
+				.collect::<Option<Vec<_>>>()?;
+			variants.join(" | ")
+		}
+		TypeDef::Sequence(seq) => with_params("Vec", &[type_signature(types, seq.type_param.id)?]),
+		TypeDef::Array(arr) => format!("[{}; {}]", type_signature(types, arr.type_param.id)?, arr.len),
+		TypeDef::Tuple(tuple) => {
+			let fields =
+				tuple.fields.iter().map(|field| type_signature(types, field.id)).collect::<Option<Vec<_>>>()?;
+			tuple_signature(&fields)
+		}
+		TypeDef::Primitive(primitive) => primitive_signature(primitive).to_owned(),
+		TypeDef::Compact(compact) => with_params("Compact", &[type_signature(types, compact.type_param.id)?]),
+		TypeDef::BitSequence(bits) => with_params(
+			"BitVec",
+			&[type_signature(types, bits.bit_store_type.id)?, type_signature(types, bits.bit_order_type.id)?],
+		),
+	};
+	Some(signature)
+}
+
+// Anonymous composite types and enum variants are rendered like a struct body.
+fn fields_signature(types: &PortableRegistry, fields: &[Field<PortableForm>]) -> Option<String> {
+	if fields.is_empty() {
+		return Some(String::new());
+	}
+	let tys = fields.iter().map(|field| type_signature(types, field.ty.id)).collect::<Option<Vec<_>>>()?;
+	if fields.iter().all(|field| field.name.is_some()) {
+		let fields = fields
+			.iter()
+			.zip(tys)
+			.map(|(field, ty)| format!("{}: {ty}", field.name.as_deref().unwrap_or_default()))
+			.collect::<Vec<_>>();
+		Some(format!(" {{ {} }}", fields.join(", ")))
+	} else {
+		Some(tuple_signature(&tys))
+	}
+}
+
+fn tuple_signature(fields: &[String]) -> String {
+	match fields {
+		[field] => format!("({field},)"),
+		fields => format!("({})", fields.join(", ")),
+	}
+}
+
+fn with_params(name: &str, params: &[String]) -> String {
+	if params.is_empty() {
+		name.to_owned()
+	} else {
+		format!("{name}<{}>", params.join(", "))
+	}
+}
+
+fn primitive_signature(primitive: &TypeDefPrimitive) -> &'static str {
+	match primitive {
+		TypeDefPrimitive::Bool => "bool",
+		TypeDefPrimitive::Char => "char",
+		TypeDefPrimitive::Str => "String",
+		TypeDefPrimitive::U8 => "u8",
+		TypeDefPrimitive::U16 => "u16",
+		TypeDefPrimitive::U32 => "u32",
+		TypeDefPrimitive::U64 => "u64",
+		TypeDefPrimitive::U128 => "u128",
+		TypeDefPrimitive::U256 => "u256",
+		TypeDefPrimitive::I8 => "i8",
+		TypeDefPrimitive::I16 => "i16",
+		TypeDefPrimitive::I32 => "i32",
+		TypeDefPrimitive::I64 => "i64",
+		TypeDefPrimitive::I128 => "i128",
+		TypeDefPrimitive::I256 => "i256",
+	}
+}
@@ -75,6 +75,14 @@ impl<V> U8Map<V> {
 			Some(item)
 		}
 	}
+
+	/// Iterate over the keys and values stored in the map, in key order.
+	pub fn iter(&self) -> impl Iterator<Item = (u8, &V)> {
+		self.indexes.iter().enumerate().filter(|(_, idx)| **idx != u8::MAX).map(|(key, idx)| {
+			let item = self.items.get(*idx as usize).expect("item must exist if in indexes");
+			(key as u8, item)
+		})
+	}
 }
 
 impl<V> FromIterator<(u8, V)> for U8Map<V> {
@@ -131,4 +139,11 @@ mod test {
 
 		assert_eq!(m.get(123), Some(&"three"));
 	}
+
+	#[test]
+	fn iterate_in_key_order() {
+		let m: U8Map<&str> = [(200, "200"), (3, "3"), (17, "17")].into_iter().collect();
+		let items: Vec<_> = m.iter().collect();
+		assert_eq!(items, vec![(3, &"3"), (17, &"17"), (200, &"200")]);
+	}
 }
@@ -15,47 +15,90 @@
 // along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::u8_map::U8Map;
-use super::{Metadata, MetadataCalls, MetadataError, MetadataExtrinsic, MetadataPalletCalls, MetadataPalletStorage};
-use frame_metadata::v14::RuntimeMetadataV14;
+use super::{
+	ExtrinsicAddressType, ExtrinsicSignatureType, Metadata, MetadataCalls, MetadataConstant, MetadataError,
+	MetadataEvents, MetadataExtrinsic, MetadataPalletCalls, MetadataPalletDetails, MetadataPalletEvents,
+	MetadataPalletStorage,
+};
+use crate::{ScaleInfoTypeId, Type, TypeId};
+use frame_metadata::v14::{PalletMetadata, RuntimeMetadataV14};
+use scale_info::{form::PortableForm, PortableRegistry, TypeDef, TypeDefPrimitive};
 
 /// Decode V14 metadata into our general Metadata struct
 pub fn decode(meta: RuntimeMetadataV14) -> Result<Metadata, MetadataError> {
 	let registry = meta.types;
+
+	// Gather some details about the extrinsic itself:
+	let extrinsic = MetadataExtrinsic {
+		version: meta.extrinsic.version,
+		address: address_type(&registry, extrinsic_type_param(&registry, meta.extrinsic.ty, "Address")),
+		signature: signature_type(&registry, extrinsic_type_param(&registry, meta.extrinsic.ty, "Signature")),
+		call_type_id: extrinsic_type_param(&registry, meta.extrinsic.ty, "Call"),
+		signed_extensions: meta.extrinsic.signed_extensions,
+	};
+
+	// V14 doesn't contain any pallet docs:
+	let pallets = meta.pallets.into_iter().map(|pallet| (pallet, Vec::new())).collect();
+
+	decode_pallets(registry, extrinsic, pallets)
+}
+
+/// Gather information about the calls/events/storage/constants in use by each pallet (given alongside its docs),
+/// returning our general Metadata struct. Later metadata versions share the V14 pallet format, and so build on this.
+pub(super) fn decode_pallets(
+	registry: PortableRegistry,
+	extrinsic: MetadataExtrinsic,
+	pallets: Vec<(PalletMetadata<PortableForm>, Vec<String>)>,
+) -> Result<Metadata, MetadataError> {
+	let mut pallet_details = Vec::new();
 	let mut pallet_calls_by_index = U8Map::new();
+	let mut pallet_events_by_index = U8Map::new();
 	let mut pallet_storage = Vec::new();
+	let mut pallet_constants = Vec::new();
 
-	// Gather some details about the extrinsic itself:
-	let extrinsic =
-		MetadataExtrinsic { version: meta.extrinsic.version, signed_extensions: meta.extrinsic.signed_extensions };
+	for (pallet, docs) in pallets {
+		pallet_details.push(MetadataPalletDetails {
+			name: pallet.name.clone(),
+			index: pallet.index,
+			calls_type_id: pallet.calls.as_ref().map(|c| c.ty.id),
+			events_type_id: pallet.event.as_ref().map(|e| e.ty.id),
+			errors_type_id: pallet.error.as_ref().map(|e| e.ty.id),
+			storage_index: pallet.storage.as_ref().map(|_| pallet_storage.len()),
+			docs,
+		});
 
-	// Gather information about the calls/storage in use:
-	for pallet in meta.pallets {
 		// capture the call information in this pallet:
 		let calls = pallet
 			.calls
 			.map(|call_md| {
-				// Get the type representing the variant of available calls:
 				let calls_type_id = call_md.ty;
-				let calls_type =
-					registry.resolve(calls_type_id.id).ok_or_else(|| MetadataError::TypeNotFound(calls_type_id.id))?;
-
-				// Expect that type to be a variant:
-				let calls_type_def = &calls_type.type_def;
-				let calls_variant = match calls_type_def {
-					scale_info::TypeDef::Variant(variant) => variant,
-					_ => {
-						return Err(MetadataError::ExpectedVariantType { got: format!("{:?}", calls_type_def) });
-					}
-				};
-
-				// Store the mapping from u8 index to variant slice index for quicker decode lookup:
-				let call_variant_indexes =
-					calls_variant.variants.iter().enumerate().map(|(idx, v)| (v.index, idx)).collect();
-
-				Ok(MetadataCalls { calls_type_id, call_variant_indexes })
+				variant_indexes(&registry, calls_type_id)
+					.map(|call_variant_indexes| MetadataCalls { calls_type_id, call_variant_indexes })
 			})
 			.transpose()?;
-		pallet_calls_by_index.insert(pallet.index, MetadataPalletCalls { name: pallet.name, calls });
+		pallet_calls_by_index.insert(pallet.index, MetadataPalletCalls { name: pallet.name.clone(), calls });
+
+		// capture the event information in this pallet:
+		let events = pallet
+			.event
+			.map(|event_md| {
+				let events_type_id = event_md.ty;
+				variant_indexes(&registry, events_type_id)
+					.map(|event_variant_indexes| MetadataEvents { events_type_id, event_variant_indexes })
+			})
+			.transpose()?;
+		pallet_events_by_index.insert(pallet.index, MetadataPalletEvents { name: pallet.name.clone(), events });
+
+		// Capture each constant in this pallet; values are decoded on demand:
+		for constant in pallet.constants {
+			pallet_constants.push(MetadataConstant {
+				pallet: pallet.name.clone(),
+				name: constant.name,
+				ty: constant.ty.id,
+				bytes: constant.value,
+				docs: constant.docs,
+			});
+		}
 
 		// Capture the storage information in this pallet:
 		if let Some(storage_metadata) = pallet.storage {
@@ -66,5 +109,92 @@ pub fn decode(meta: RuntimeMetadataV14) -> Result<Metadata, MetadataError> {
 		}
 	}
 
-	Ok(Metadata { pallet_calls_by_index, pallet_storage: pallet_storage.into(), extrinsic, types: registry })
+	Ok(Metadata {
+		version: 14,
+		pallets: pallet_details.into(),
+		pallet_calls_by_index,
+		pallet_events_by_index,
+		pallet_storage: pallet_storage.into(),
+		pallet_constants: pallet_constants.into(),
+		extrinsic,
+		outer_enums: None,
+		runtime_apis: Vec::new(),
+		custom_values: Vec::new(),
+		types: registry,
+	})
+}
+
+/// Resolve the type given, expecting it to be a variant (as pallet calls and events are), and return
+/// a mapping from the u8 index of each variant to its position in the variant slice, for quicker decode lookup.
+fn variant_indexes(registry: &PortableRegistry, type_id: ScaleInfoTypeId) -> Result<U8Map<usize>, MetadataError> {
+	let ty = registry.resolve(type_id.id).ok_or(MetadataError::TypeNotFound(type_id.id))?;
+
+	// Expect that type to be a variant:
+	let type_def = &ty.type_def;
+	let variant = match type_def {
+		scale_info::TypeDef::Variant(variant) => variant,
+		_ => {
+			return Err(MetadataError::ExpectedVariantType { got: format!("{:?}", type_def) });
+		}
+	};
+
+	Ok(variant.variants.iter().enumerate().map(|(idx, v)| (v.index, idx)).collect())
+}
+
+/// Find the type ID of the named type parameter of the extrinsic type (eg `Address` or `Signature`),
+/// if the metadata describes it.
+fn extrinsic_type_param(registry: &PortableRegistry, extrinsic_ty: ScaleInfoTypeId, name: &str) -> Option<TypeId> {
+	registry
+		.resolve(extrinsic_ty.id)?
+		.type_params
+		.iter()
+		.find(|param| param.name == name)
+		.and_then(|param| param.ty)
+		.map(|ty| ty.id)
+}
+
+/// Work out whether the extrinsic address type is the standard `MultiAddress<AccountId32, u32 | ()>`, which
+/// we can decode directly. If we don't know the address type, we assume that it's standard.
+pub(super) fn address_type(registry: &PortableRegistry, type_id: Option<TypeId>) -> ExtrinsicAddressType {
+	let Some(id) = type_id else {
+		return ExtrinsicAddressType::MultiAddress { type_id: None, unit_index: false };
+	};
+	let param = |ty: &Type, name: &str| {
+		let id = ty.type_params.iter().find(|p| p.name == name)?.ty?.id;
+		registry.resolve(id)
+	};
+	let standard = registry
+		.resolve(id)
+		.filter(|ty| ty.path.segments == ["sp_runtime", "multiaddress", "MultiAddress"])
+		.and_then(|ty| {
+			let account_id = param(ty, "AccountId")?;
+			let account_index = param(ty, "AccountIndex")?;
+			if account_id.path.segments != ["sp_core", "crypto", "AccountId32"] {
+				return None;
+			}
+			match &account_index.type_def {
+				TypeDef::Primitive(TypeDefPrimitive::U32) => Some(false),
+				TypeDef::Tuple(tuple) if tuple.fields.is_empty() => Some(true),
+				_ => None,
+			}
+		});
+
+	match standard {
+		Some(unit_index) => ExtrinsicAddressType::MultiAddress { type_id: Some(id), unit_index },
+		None => ExtrinsicAddressType::Other(id),
+	}
+}
+
+/// Work out whether the extrinsic signature type is the standard `MultiSignature`, which we can decode
+/// directly. If we don't know the signature type, we assume that it's standard.
+pub(super) fn signature_type(registry: &PortableRegistry, type_id: Option<TypeId>) -> ExtrinsicSignatureType {
+	let Some(id) = type_id else {
+		return ExtrinsicSignatureType::MultiSignature { type_id: None };
+	};
+	match registry.resolve(id) {
+		Some(ty) if ty.path.segments == ["sp_runtime", "MultiSignature"] => {
+			ExtrinsicSignatureType::MultiSignature { type_id: Some(id) }
+		}
+		_ => ExtrinsicSignatureType::Other(id),
+	}
 }
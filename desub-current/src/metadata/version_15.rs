@@ -0,0 +1,103 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::version_14::{address_type, decode_pallets, signature_type};
+use super::{
+	Metadata, MetadataCustomValue, MetadataError, MetadataExtrinsic, MetadataOuterEnums, MetadataRuntimeApi,
+	MetadataRuntimeApiMethod, MetadataRuntimeApiParam,
+};
+use frame_metadata::{v14, v15::RuntimeMetadataV15};
+
+/// Decode V15 metadata into our general Metadata struct. The pallet and extrinsic information is
+/// laid out in the same way as in V14, and so we lean on that, adding the details new to V15 after.
+pub fn decode(meta: RuntimeMetadataV15) -> Result<Metadata, MetadataError> {
+	let registry = meta.types;
+
+	// V15 hands us the extrinsic address/call/signature types directly:
+	let extrinsic = MetadataExtrinsic {
+		version: meta.extrinsic.version,
+		address: address_type(&registry, Some(meta.extrinsic.address_ty.id)),
+		signature: signature_type(&registry, Some(meta.extrinsic.signature_ty.id)),
+		call_type_id: Some(meta.extrinsic.call_ty.id),
+		signed_extensions: meta
+			.extrinsic
+			.signed_extensions
+			.into_iter()
+			.map(|ext| v14::SignedExtensionMetadata {
+				identifier: ext.identifier,
+				ty: ext.ty,
+				additional_signed: ext.additional_signed,
+			})
+			.collect(),
+	};
+
+	// Pallets are the same as in V14 bar the docs, which we hand over alongside each:
+	let pallets = meta
+		.pallets
+		.into_iter()
+		.map(|pallet| {
+			let v14_pallet = v14::PalletMetadata {
+				name: pallet.name,
+				storage: pallet.storage,
+				calls: pallet.calls,
+				event: pallet.event,
+				constants: pallet.constants,
+				error: pallet.error,
+				index: pallet.index,
+			};
+			(v14_pallet, pallet.docs)
+		})
+		.collect();
+
+	let mut metadata = decode_pallets(registry, extrinsic, pallets)?;
+
+	metadata.version = 15;
+	metadata.outer_enums = Some(MetadataOuterEnums {
+		call_enum_ty: meta.outer_enums.call_enum_ty.id,
+		event_enum_ty: meta.outer_enums.event_enum_ty.id,
+		error_enum_ty: meta.outer_enums.error_enum_ty.id,
+	});
+	metadata.runtime_apis = meta
+		.apis
+		.into_iter()
+		.map(|api| MetadataRuntimeApi {
+			name: api.name,
+			methods: api
+				.methods
+				.into_iter()
+				.map(|method| MetadataRuntimeApiMethod {
+					name: method.name,
+					inputs: method
+						.inputs
+						.into_iter()
+						.map(|param| MetadataRuntimeApiParam { name: param.name, ty: param.ty.id })
+						.collect(),
+					output: method.output.id,
+					docs: method.docs,
+				})
+				.collect(),
+			docs: api.docs,
+		})
+		.collect();
+	metadata.custom_values = meta
+		.custom
+		.map
+		.into_iter()
+		.map(|(name, value)| MetadataCustomValue { name, ty: value.ty.id, bytes: value.value })
+		.collect();
+
+	Ok(metadata)
+}
@@ -0,0 +1,179 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Given a decoded signed [`Extrinsic`], this module exposes the functionality to rebuild the payload
+//! that was signed and to check the extrinsic signature against it, without needing access to a node.
+//!
+//! The signer payload is made up of the call data, the signed extension bytes from the extrinsic and the
+//! "additional signed" data for each signed extension. The latter isn't part of the extrinsic, and so must
+//! be provided via [`AdditionalSigned`]. See [`verify_signature()`] and [`signer_payload()`].
+
+use crate::decoder::{Address, Extrinsic, Signature};
+use crate::encoder::encode_value_by_id;
+use crate::metadata::Metadata;
+use crate::TypeId;
+use parity_scale_codec::Encode;
+use scale_value::{Value, ValueDef};
+use sp_core::{blake2_256, ecdsa, ed25519, sr25519, Pair, H256};
+use sp_runtime::{MultiAddress, MultiSignature};
+
+/// Signer payloads longer than this many bytes are hashed with blake2-256 before being signed.
+pub const MAX_UNHASHED_PAYLOAD_LEN: usize = 256;
+
+#[derive(Debug, thiserror::Error)]
+pub enum VerifyError {
+	#[error("The extrinsic is not signed")]
+	NotSigned,
+	#[error("Only signatures from MultiAddress::Id addresses can be verified")]
+	UnsupportedAddress,
+	#[error("Only MultiSignature signatures can be verified")]
+	UnsupportedSignature,
+	#[error("Expected {expected} signed extensions but the extrinsic has {got}")]
+	WrongNumberOfExtensions { expected: usize, got: usize },
+	#[error("No additional signed data was provided for the signed extension '{0}'")]
+	MissingAdditionalSigned(String),
+}
+
+/// The "additional signed" data which is included in the signer payload but not in the extrinsic itself.
+///
+/// The standard `CheckSpecVersion`, `CheckTxVersion`, `CheckGenesis` and `CheckMortality` extensions are
+/// given their additional data from the named fields here. Any other extension with additional data must
+/// be given it in `other`, else it's assumed to have none.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AdditionalSigned {
+	/// The spec version of the runtime that the extrinsic was signed for.
+	pub spec_version: u32,
+	/// The transaction version of the runtime that the extrinsic was signed for.
+	pub transaction_version: u32,
+	/// The genesis hash of the chain.
+	pub genesis_hash: H256,
+	/// The hash of the block that a mortal extrinsic's era begins at. Immortal extrinsics use the
+	/// genesis hash instead, and so this can be left as `None` for them.
+	pub block_hash: Option<H256>,
+	/// Pre-encoded additional data for other signed extensions, by name. This takes precedence
+	/// over the fields above.
+	pub other: Vec<(String, Vec<u8>)>,
+}
+
+/// Rebuild the bytes that were signed to produce the signature of the given extrinsic. Following substrate,
+/// if the payload is longer than [`MAX_UNHASHED_PAYLOAD_LEN`] bytes, its blake2-256 hash is returned instead.
+pub fn signer_payload(
+	metadata: &Metadata,
+	extrinsic: &Extrinsic,
+	additional_signed: &AdditionalSigned,
+) -> Result<Vec<u8>, VerifyError> {
+	let signature = extrinsic.signature.as_ref().ok_or(VerifyError::NotSigned)?;
+	let extension_meta = metadata.extrinsic().signed_extensions();
+
+	if extension_meta.len() != signature.extensions.len() {
+		return Err(VerifyError::WrongNumberOfExtensions {
+			expected: extension_meta.len(),
+			got: signature.extensions.len(),
+		});
+	}
+
+	// Layout: (call_data, signed_extensions, additional_signed). The signed extensions are taken
+	// as-is from the extrinsic, so that we don't rely on their decoded values re-encoding exactly.
+	let mut payload = extrinsic.call_data.bytes.clone();
+	payload.extend(&signature.extension_bytes);
+	for (ext, (name, value)) in extension_meta.iter().zip(&signature.extensions) {
+		let bytes = additional_signed_bytes(metadata, ext.additional_signed.id, name, value, additional_signed)?;
+		payload.extend(bytes);
+	}
+
+	if payload.len() > MAX_UNHASHED_PAYLOAD_LEN {
+		payload = blake2_256(&payload).to_vec();
+	}
+	Ok(payload)
+}
+
+/// Verify the signature of a signed extrinsic against the signer payload rebuilt by [`signer_payload()`].
+/// Sr25519, Ed25519 and ECDSA signatures from a `MultiAddress::Id` address are supported.
+///
+/// Returns `Ok(false)` if the signature is not valid for the payload, which will be the case if the extrinsic
+/// has been tampered with, or if the wrong [`AdditionalSigned`] data was provided.
+pub fn verify_signature(
+	metadata: &Metadata,
+	extrinsic: &Extrinsic,
+	additional_signed: &AdditionalSigned,
+) -> Result<bool, VerifyError> {
+	let signature = extrinsic.signature.as_ref().ok_or(VerifyError::NotSigned)?;
+	let account = match &signature.address {
+		Address::MultiAddress(MultiAddress::Id(account)) => account,
+		_ => return Err(VerifyError::UnsupportedAddress),
+	};
+	let account: &[u8; 32] = account.as_ref();
+	let multi_signature = match &signature.signature {
+		Signature::MultiSignature(sig) => sig,
+		Signature::Value(_) => return Err(VerifyError::UnsupportedSignature),
+	};
+
+	let payload = signer_payload(metadata, extrinsic, additional_signed)?;
+
+	let is_valid = match multi_signature {
+		MultiSignature::Sr25519(sig) => sr25519::Pair::verify(sig, &payload, &sr25519::Public::from_raw(*account)),
+		MultiSignature::Ed25519(sig) => ed25519::Pair::verify(sig, &payload, &ed25519::Public::from_raw(*account)),
+		// ECDSA accounts are the blake2-256 hash of the compressed public key.
+		MultiSignature::Ecdsa(sig) => {
+			sig.recover(&payload).is_some_and(|public: ecdsa::Public| &blake2_256(public.as_ref()) == account)
+		}
+	};
+	Ok(is_valid)
+}
+
+// Work out the additional signed bytes for a single signed extension.
+fn additional_signed_bytes(
+	metadata: &Metadata,
+	ty: u32,
+	name: &str,
+	value: &Value<TypeId>,
+	additional_signed: &AdditionalSigned,
+) -> Result<Vec<u8>, VerifyError> {
+	if let Some((_, bytes)) = additional_signed.other.iter().find(|(n, _)| n == name) {
+		return Ok(bytes.clone());
+	}
+
+	let bytes = match name {
+		"CheckSpecVersion" => additional_signed.spec_version.encode(),
+		"CheckTxVersion" => additional_signed.transaction_version.encode(),
+		"CheckGenesis" => additional_signed.genesis_hash.encode(),
+		"CheckMortality" | "CheckEra" => {
+			// The extension usually wraps the `Era`, so look through any single field composites to find it.
+			let mut era = value;
+			while let ValueDef::Composite(c) = &era.value {
+				let mut values = c.values();
+				match (values.next(), values.next()) {
+					(Some(inner), None) => era = inner,
+					_ => break,
+				}
+			}
+			let is_immortal = matches!(&era.value, ValueDef::Variant(v) if v.name == "Immortal");
+			if is_immortal {
+				additional_signed.genesis_hash.encode()
+			} else {
+				let block_hash = additional_signed
+					.block_hash
+					.ok_or_else(|| VerifyError::MissingAdditionalSigned(name.to_owned()))?;
+				block_hash.encode()
+			}
+		}
+		// Anything else is expected to have no additional data (ie `()`); encoding an
+		// empty composite will fail if that's not the case.
+		_ => encode_value_by_id(metadata, ty, &Value::unnamed_composite(vec![]))
+			.map_err(|_| VerifyError::MissingAdditionalSigned(name.to_owned()))?,
+	};
+	Ok(bytes)
+}
@@ -0,0 +1,138 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Metadata fixtures shared between the test files. Not every test file uses every helper.
+#![allow(dead_code)]
+
+use desub_current::{
+	scale_info::{form::PortableForm, PortableRegistry, PortableType, Type, TypeDef, Variant},
+	Metadata,
+};
+use frame_metadata::{
+	v14::{self, RuntimeMetadataV14},
+	v15::{CustomMetadata, ExtrinsicMetadata, OuterEnums, PalletMetadata, RuntimeMetadataV15, SignedExtensionMetadata},
+	RuntimeMetadata, RuntimeMetadataPrefixed,
+};
+use parity_scale_codec::{Decode, Encode};
+
+pub static V14_METADATA_POLKADOT_SCALE: &[u8] = include_bytes!("../data/v14_metadata_polkadot.scale");
+
+pub fn metadata() -> Metadata {
+	Metadata::from_bytes(V14_METADATA_POLKADOT_SCALE).expect("valid metadata")
+}
+
+pub fn runtime_metadata() -> RuntimeMetadataV14 {
+	let RuntimeMetadataPrefixed(_, RuntimeMetadata::V14(v14)) =
+		RuntimeMetadataPrefixed::decode(&mut &*V14_METADATA_POLKADOT_SCALE).unwrap()
+	else {
+		panic!("expected V14 metadata")
+	};
+	v14
+}
+
+pub fn to_metadata(v14: RuntimeMetadataV14) -> Metadata {
+	Metadata::from_runtime_metadata(RuntimeMetadata::V14(v14)).expect("valid metadata")
+}
+
+pub fn pallet<'a>(v14: &'a mut RuntimeMetadataV14, name: &str) -> &'a mut v14::PalletMetadata<PortableForm> {
+	v14.pallets.iter_mut().find(|p| p.name == name).expect("pallet exists")
+}
+
+pub fn variant<'a>(types: &'a mut PortableRegistry, ty: u32, name: &str) -> &'a mut Variant<PortableForm> {
+	let TypeDef::Variant(def) = &mut types.types[ty as usize].ty.type_def else { panic!("expected a variant type") };
+	def.variants.iter_mut().find(|v| v.name == name).expect("variant exists")
+}
+
+// Add a copy of an existing type to the registry, returning the ID of the copy.
+pub fn copy_type(types: &mut PortableRegistry, ty: u32) -> u32 {
+	let id = types.types.len() as u32;
+	let ty = types.types[ty as usize].ty.clone();
+	types.types.push(PortableType { id, ty });
+	id
+}
+
+// Find the ID of the first type in the registry which matches the predicate.
+pub fn find_type(types: &PortableRegistry, f: impl Fn(&Type<PortableForm>) -> bool) -> u32 {
+	types.types.iter().find(|t| f(&t.ty)).map(|t| t.id).expect("type exists")
+}
+
+pub fn type_id_by_path(types: &PortableRegistry, path: &[&str]) -> u32 {
+	find_type(types, |ty| ty.path.segments == path)
+}
+
+// The ID of a named generic parameter of some type, eg the `Call` in `UncheckedExtrinsic<Address, Call, ..>`.
+fn type_param(types: &PortableRegistry, ty: u32, name: &str) -> u32 {
+	let ty = &types.types[ty as usize].ty;
+	ty.type_params.iter().find(|p| p.name == name).and_then(|p| p.ty).expect("type param exists").id
+}
+
+/// Build V15 metadata from the V14 polkadot metadata. The extrinsic and outer enum types are taken from the
+/// V14 metadata, and `customise` can then add V15 specific details like runtime APIs before it's decoded.
+pub fn v15_metadata(customise: impl FnOnce(&mut RuntimeMetadataV15)) -> Metadata {
+	let RuntimeMetadataV14 { types, pallets, extrinsic, ty } = runtime_metadata();
+
+	let ext_ty = extrinsic.ty.id;
+	let extrinsic = ExtrinsicMetadata {
+		version: extrinsic.version,
+		address_ty: type_param(&types, ext_ty, "Address").into(),
+		call_ty: type_param(&types, ext_ty, "Call").into(),
+		signature_ty: type_param(&types, ext_ty, "Signature").into(),
+		extra_ty: type_param(&types, ext_ty, "Extra").into(),
+		signed_extensions: extrinsic
+			.signed_extensions
+			.into_iter()
+			.map(|e| SignedExtensionMetadata {
+				identifier: e.identifier,
+				ty: e.ty,
+				additional_signed: e.additional_signed,
+			})
+			.collect(),
+	};
+	// This runtime predates the outer error enum, so we make do with the System pallet errors instead:
+	let system_errors = pallets.iter().find(|p| p.name == "System").and_then(|p| p.error.as_ref()).unwrap().ty;
+	let outer_enums = OuterEnums {
+		call_enum_ty: extrinsic.call_ty,
+		event_enum_ty: type_id_by_path(&types, &["polkadot_runtime", "Event"]).into(),
+		error_enum_ty: system_errors,
+	};
+	let pallets = pallets
+		.into_iter()
+		.map(|p| PalletMetadata {
+			name: p.name,
+			storage: p.storage,
+			calls: p.calls,
+			event: p.event,
+			constants: p.constants,
+			error: p.error,
+			index: p.index,
+			docs: vec![],
+		})
+		.collect();
+
+	let mut v15 = RuntimeMetadataV15 {
+		types,
+		pallets,
+		extrinsic,
+		ty,
+		apis: vec![],
+		outer_enums,
+		custom: CustomMetadata { map: Default::default() },
+	};
+	customise(&mut v15);
+
+	let bytes = RuntimeMetadataPrefixed(frame_metadata::META_RESERVED, RuntimeMetadata::V15(v15)).encode();
+	Metadata::from_bytes(&bytes).expect("valid metadata")
+}
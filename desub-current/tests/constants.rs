@@ -0,0 +1,86 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+mod common;
+
+use common::{metadata, pallet, runtime_metadata, to_metadata};
+use desub_current::{metadata::MetadataError, Value};
+use scale_value::At;
+
+#[test]
+fn balances_existential_deposit() {
+	let meta = metadata();
+	let constant = meta.constant("Balances", "ExistentialDeposit").expect("constant exists");
+
+	assert_eq!(constant.pallet(), "Balances");
+	assert_eq!(constant.name(), "ExistentialDeposit");
+	assert_eq!(constant.ty(), 6);
+	assert_eq!(constant.value(&meta).unwrap().remove_context(), Value::u128(10_000_000_000));
+	assert_eq!(constant.bytes(), 10_000_000_000u128.to_le_bytes());
+	assert!(!constant.docs().is_empty());
+}
+
+#[test]
+fn composite_constant() {
+	let meta = metadata();
+	let constant = meta.constant("System", "SS58Prefix").expect("constant exists");
+	assert_eq!(constant.value(&meta).unwrap().remove_context(), Value::u128(0));
+
+	// Struct-like constants decode into composite values:
+	let block_length = meta.constant("System", "BlockLength").expect("constant exists");
+	let block_length = block_length.value(&meta).unwrap();
+	let max = block_length.at("max").expect("has max field");
+	assert_eq!(max.at("normal").and_then(|v| v.as_u128()), Some(3_932_160));
+	assert_eq!(max.at("operational").and_then(|v| v.as_u128()), Some(5 * 1024 * 1024));
+}
+
+#[test]
+fn missing_constant() {
+	let meta = metadata();
+	assert!(meta.constant("Balances", "NotAConstant").is_none());
+	assert!(meta.constant("NotAPallet", "ExistentialDeposit").is_none());
+}
+
+#[test]
+fn iterate_constants() {
+	let meta = metadata();
+	let balances: Vec<_> = meta.constants().filter(|c| c.pallet() == "Balances").map(|c| c.name()).collect();
+	assert_eq!(balances, vec!["ExistentialDeposit", "MaxLocks", "MaxReserves"]);
+}
+
+#[test]
+fn bad_constants_do_not_prevent_decoding_metadata() {
+	let mut v14 = runtime_metadata();
+	let balances = pallet(&mut v14, "Balances");
+	// Too many bytes for a u128:
+	balances.constants[0].value.push(0);
+	// Too few bytes for a u32:
+	balances.constants[1].value.pop();
+
+	let meta = to_metadata(v14);
+
+	let existential_deposit = meta.constant("Balances", "ExistentialDeposit").expect("constant exists");
+	let res = existential_deposit.value(&meta);
+	assert!(matches!(res, Err(MetadataError::ExcessConstantBytes { remaining: 1, .. })));
+
+	let max_locks = meta.constant("Balances", "MaxLocks").expect("constant exists");
+	let res = max_locks.value(&meta);
+	assert!(matches!(res, Err(MetadataError::CannotDecodeConstant { .. })));
+
+	// Other constants are unaffected:
+	let ss58_prefix = meta.constant("System", "SS58Prefix").expect("constant exists");
+	assert_eq!(ss58_prefix.value(&meta).unwrap().remove_context(), Value::u128(0));
+}
@@ -0,0 +1,185 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+mod common;
+
+use common::metadata;
+use desub_current::{
+	decoder::{self, DecodeError},
+	scale_info::{
+		form::PortableForm, PortableRegistry, PortableType, Type, TypeDef, TypeDefPrimitive, TypeDefSequence,
+		TypeDefTuple,
+	},
+	Metadata,
+};
+
+fn to_bytes(hex_str: &str) -> Vec<u8> {
+	let hex_str = hex_str.strip_prefix("0x").expect("0x should prefix hex encoded bytes");
+	hex::decode(hex_str).expect("valid bytes from hex")
+}
+
+// Balances.transfer (amount: 12345), without the length prefix.
+const SIGNED_TRANSFER: &str = "0x8400d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d016ada9b477ef454972200e098f1186d4a2aeee776f1f6a68609797f5ba052906ad2427bdca865442158d118e2dfc82226077e4dfdff975d005685bab66eefa38a150200000500001cbd2d43530a44705ad088af313e18f80b53ef16b36177cd4b77b846f2a5f07ce5c0";
+
+#[test]
+fn annotate_signed_extrinsic() {
+	let meta = metadata();
+	let bytes = to_bytes(SIGNED_TRANSFER);
+
+	let annotated = decoder::decode_extrinsic_annotated(&meta, &bytes);
+	let ext = annotated.result.as_ref().expect("can decode extrinsic");
+	assert_eq!(&*ext.call_data.ty.name, "transfer");
+
+	let find = |path: &str| {
+		annotated.annotations.iter().find(|a| a.path == path).unwrap_or_else(|| panic!("no annotation for {path}"))
+	};
+
+	assert_eq!(find("version").range, 0..1);
+	assert_eq!(find("signature").range, 1..103);
+	assert_eq!(find("signature.address").range, 1..34);
+	assert_eq!(find("signature.signature").range, 34..99);
+	assert_eq!(find("signature.extensions.CheckMortality").range, 99..101);
+	assert_eq!(find("signature.extensions.CheckNonce").range, 101..102);
+	assert_eq!(find("call").range, 103..140);
+	assert_eq!(find("call").value.as_deref(), Some("Balances.transfer"));
+	assert_eq!(find("call.pallet_index").range, 103..104);
+	assert_eq!(find("call.call_index").range, 104..105);
+
+	// Nested argument values are annotated too:
+	let dest = find("call.args.dest");
+	assert_eq!((dest.range.clone(), dest.depth, dest.value.as_deref()), (105..138, 1, Some("Id")));
+	assert_eq!(find("call.args.dest.variant_index").range, 105..106);
+	let account = find("call.args.dest.0.0");
+	assert_eq!((account.range.clone(), account.depth), (106..138, 3));
+	assert_eq!(account.value.as_deref(), Some("0x1cbd2d43530a44705ad088af313e18f80b53ef16b36177cd4b77b846f2a5f07c"));
+	let value = find("call.args.value");
+	assert_eq!((value.range.clone(), value.value.as_deref()), (138..140, Some("12345")));
+
+	// Each annotation at depth 0 follows on from the last:
+	let top_level: Vec<_> = annotated.annotations.iter().filter(|a| a.depth == 0).map(|a| a.range.clone()).collect();
+	assert_eq!(top_level, vec![0..1, 1..103, 103..140]);
+}
+
+#[test]
+fn annotate_failure_reports_offset() {
+	let meta = metadata();
+
+	// The signed transfer above, but with an unknown call index:
+	let mut bytes = to_bytes(SIGNED_TRANSFER);
+	bytes[104] = 0xff;
+
+	let annotated = decoder::decode_extrinsic_annotated(&meta, &bytes);
+	let err = annotated.result.as_ref().expect_err("should fail to decode");
+	assert_eq!(err.offset, 103);
+	assert!(matches!(err.error, DecodeError::CannotFindCall(5, 255)));
+
+	// We still have annotations up to the point of failure:
+	assert!(annotated.annotations.iter().any(|a| a.path == "signature.address"));
+	assert!(!annotated.annotations.iter().any(|a| a.path.starts_with("call.args")));
+}
+
+#[test]
+fn render_annotated_hex_dump() {
+	let meta = metadata();
+	let bytes = to_bytes("0x04480104080c1014");
+
+	let annotated = decoder::decode_extrinsic_annotated(&meta, &bytes);
+	let dump = decoder::render_hex_dump(&bytes, &annotated);
+	let lines: Vec<_> = dump.lines().collect();
+
+	assert_eq!(lines.len(), annotated.annotations.len());
+	assert!(lines[0].starts_with("     0..1      04"));
+	assert!(lines[0].ends_with("version: 4 (Bare)"));
+	assert!(lines.iter().any(|l| l.contains("call: Auctions.bid")));
+
+	// Failures are rendered on the last line:
+	let bytes = to_bytes("0x04ff00");
+	let annotated = decoder::decode_extrinsic_annotated(&meta, &bytes);
+	let dump = decoder::render_hex_dump(&bytes, &annotated);
+	let last_line = dump.lines().last().expect("has lines");
+	assert!(last_line.starts_with("     1..3      ff00"));
+	assert!(last_line.contains("error: Cannot find call"));
+}
+
+// The polkadot metadata, but with `System.remark` taking a sequence of the given item type instead of bytes.
+fn metadata_with_remark_of(item: impl FnOnce(&mut PortableRegistry) -> u32) -> Metadata {
+	let mut v14 = common::runtime_metadata();
+	let item_ty = item(&mut v14.types);
+	let seq_ty = v14.types.types.len() as u32;
+	let type_def = TypeDef::Sequence(TypeDefSequence { type_param: item_ty.into() });
+	let ty = Type::<PortableForm> { path: Default::default(), type_params: vec![], type_def, docs: vec![] };
+	v14.types.types.push(PortableType { id: seq_ty, ty });
+
+	let calls_ty = common::pallet(&mut v14, "System").calls.as_ref().expect("System has calls").ty.id;
+	common::variant(&mut v14.types, calls_ty, "remark").fields[0].ty = seq_ty.into();
+	common::to_metadata(v14)
+}
+
+fn unit_type(types: &mut PortableRegistry) -> u32 {
+	let id = types.types.len() as u32;
+	let type_def = TypeDef::Tuple(TypeDefTuple { fields: vec![] });
+	types
+		.types
+		.push(PortableType { id, ty: Type { path: Default::default(), type_params: vec![], type_def, docs: vec![] } });
+	id
+}
+
+#[test]
+fn annotate_huge_zero_sized_sequence() {
+	let meta = metadata_with_remark_of(unit_type);
+
+	// System.remark with a `Vec<()>` claiming to have u32::MAX items:
+	let bytes = to_bytes("0x04000103ffffffff");
+	let annotated = decoder::decode_extrinsic_annotated(&meta, &bytes);
+	let err = annotated.result.as_ref().expect_err("should fail to decode");
+	assert_eq!(err.offset, 3);
+	assert!(matches!(err.error, DecodeError::ZeroSizedSequenceTooLong(u32::MAX)));
+
+	// Shorter sequences are fine, and their items are annotated together:
+	let bytes = to_bytes("0x0400010c");
+	let annotated = decoder::decode_extrinsic_annotated(&meta, &bytes);
+	assert!(annotated.result.is_ok());
+	let items: Vec<_> = annotated.annotations.iter().filter(|a| a.path.starts_with("call.args.remark[")).collect();
+	assert_eq!(items.len(), 1);
+	assert_eq!((items[0].path.as_str(), items[0].range.clone()), ("call.args.remark[0..3]", 4..4));
+	assert_eq!(items[0].value.as_deref(), Some("3 x ()"));
+}
+
+#[test]
+fn annotate_sequence_longer_than_input() {
+	let meta = metadata_with_remark_of(|types| {
+		common::find_type(types, |ty| ty.type_def == TypeDef::Primitive(TypeDefPrimitive::U32))
+	});
+
+	// System.remark with a `Vec<u32>` claiming to have u32::MAX items:
+	let bytes = to_bytes("0x04000103ffffffff01000000");
+	let annotated = decoder::decode_extrinsic_annotated(&meta, &bytes);
+	let err = annotated.result.as_ref().expect_err("should fail to decode");
+	assert_eq!(err.offset, 3);
+	assert!(matches!(err.error, DecodeError::EarlyEof(_)));
+}
+
+#[test]
+fn annotate_reports_excess_bytes() {
+	let meta = metadata();
+	let bytes = to_bytes("0x04480104080c101400");
+
+	let annotated = decoder::decode_extrinsic_annotated(&meta, &bytes);
+	let err = annotated.result.as_ref().expect_err("should fail to decode");
+	assert_eq!(err.offset, 8);
+	assert!(matches!(err.error, DecodeError::ExcessBytes(1)));
+	assert!(annotated.annotations.iter().any(|a| a.path == "call"));
+}
@@ -0,0 +1,126 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+mod common;
+
+use common::metadata;
+use desub_current::{decoder, encoder, Metadata, Value};
+use parity_scale_codec::{Compact, Encode};
+use sp_keyring::AccountKeyring;
+
+fn remark(meta: &Metadata) -> Vec<u8> {
+	encoder::encode_call_data(meta, "System", "remark", &[Value::from_bytes(b"hello")]).unwrap()
+}
+
+fn transfer(meta: &Metadata) -> Vec<u8> {
+	let dest = Value::unnamed_variant("Id", vec![Value::from_bytes(AccountKeyring::Bob.to_account_id())]);
+	encoder::encode_call_data(meta, "Balances", "transfer", &[dest, Value::u128(12345)]).unwrap()
+}
+
+// Utility.batch_all(calls)
+fn batch_all(calls: &[Vec<u8>]) -> Vec<u8> {
+	[vec![26, 2], Compact(calls.len() as u32).encode(), calls.concat()].concat()
+}
+
+// Proxy.proxy(Alice, None, call)
+fn proxy(call: &[u8]) -> Vec<u8> {
+	[vec![29, 0], AccountKeyring::Alice.to_account_id().encode(), vec![0], call.to_vec()].concat()
+}
+
+// Multisig.as_multi(2, [Bob], None, call, false, 1000), where call is an `OpaqueCall`.
+fn as_multi(call: &[u8]) -> Vec<u8> {
+	[
+		vec![30, 1],
+		2u16.encode(),
+		vec![AccountKeyring::Bob.to_account_id()].encode(),
+		vec![0],
+		call.to_vec().encode(),
+		vec![0],
+		1000u64.encode(),
+	]
+	.concat()
+}
+
+#[test]
+fn call_without_nested_calls_is_a_leaf() {
+	let meta = metadata();
+	let bytes = transfer(&meta);
+	let call_data = decoder::decode_call_data(&meta, &mut &*bytes).unwrap();
+
+	let tree = decoder::decode_call_tree(&meta, &call_data);
+	assert_eq!(&*tree.pallet_name, "Balances");
+	assert_eq!(&*tree.ty.name, "transfer");
+	assert_eq!(tree.depth, 0);
+	assert_eq!(tree.path, "call");
+	assert_eq!(tree.arguments, call_data.arguments);
+	assert!(tree.children.is_empty());
+}
+
+#[test]
+fn finds_calls_nested_in_batches_and_proxies() {
+	let meta = metadata();
+	let bytes = batch_all(&[proxy(&transfer(&meta)), remark(&meta)]);
+	let call_data = decoder::decode_call_data(&meta, &mut &*bytes).unwrap();
+
+	let tree = decoder::decode_call_tree(&meta, &call_data);
+	let calls: Vec<_> = tree.iter().map(|c| (c.depth, &*c.pallet_name, &*c.ty.name, c.path.as_str())).collect();
+	assert_eq!(
+		calls,
+		vec![
+			(0, "Utility", "batch_all", "call"),
+			(1, "Proxy", "proxy", "call.args.calls[0]"),
+			(2, "Balances", "transfer", "call.args.calls[0].args.call"),
+			(1, "System", "remark", "call.args.calls[1]"),
+		]
+	);
+
+	// The nested transfer has the same arguments as if it were decoded on its own:
+	let transfer_bytes = transfer(&meta);
+	let transfer_data = decoder::decode_call_data(&meta, &mut &*transfer_bytes).unwrap();
+	let nested_transfer = &tree.children[0].children[0];
+	let strip = |args: &[Value<u32>]| args.iter().cloned().map(|a| a.remove_context()).collect::<Vec<_>>();
+	assert_eq!(strip(&nested_transfer.arguments), strip(&transfer_data.arguments));
+}
+
+#[test]
+fn finds_opaque_calls_in_multisigs() {
+	let meta = metadata();
+	let bytes = proxy(&as_multi(&batch_all(&[transfer(&meta)])));
+	let call_data = decoder::decode_call_data(&meta, &mut &*bytes).unwrap();
+
+	let tree = decoder::decode_call_tree(&meta, &call_data);
+	let calls: Vec<_> = tree.iter().map(|c| (c.depth, &*c.ty.name, c.path.as_str())).collect();
+	assert_eq!(
+		calls,
+		vec![
+			(0, "proxy", "call"),
+			(1, "as_multi", "call.args.call"),
+			(2, "batch_all", "call.args.call.args.call"),
+			(3, "transfer", "call.args.call.args.call.args.calls[0]"),
+		]
+	);
+}
+
+#[test]
+fn invalid_opaque_calls_are_ignored() {
+	let meta = metadata();
+	let bytes = as_multi(&[0xff, 0xff]);
+	let call_data = decoder::decode_call_data(&meta, &mut &*bytes).unwrap();
+
+	let tree = decoder::decode_call_tree(&meta, &call_data);
+	assert_eq!(&*tree.ty.name, "as_multi");
+	assert!(tree.children.is_empty());
+}
@@ -0,0 +1,101 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+mod common;
+
+use common::metadata;
+use desub_current::{
+	decoder::{self, DecodeError},
+	Value,
+};
+use parity_scale_codec::Encode;
+
+#[test]
+fn module_error_from_u8_and_u8_array() {
+	let meta = metadata();
+
+	for error in [&[2u8][..], &[2, 0, 0, 0]] {
+		let error = decoder::decode_module_error(&meta, 5, error).expect("can find error");
+		assert_eq!(error.pallet_name, "Balances");
+		assert_eq!(error.ty.name, "InsufficientBalance");
+		assert!(!error.ty.docs.is_empty());
+	}
+}
+
+#[test]
+fn module_error_not_found() {
+	let meta = metadata();
+
+	let res = decoder::decode_module_error(&meta, 5, &[255]);
+	assert!(matches!(res, Err(DecodeError::CannotFindError(5, 255))));
+
+	let res = decoder::decode_module_error(&meta, 255, &[0]);
+	assert!(matches!(res, Err(DecodeError::CannotFindError(255, 0))));
+
+	let res = decoder::decode_module_error(&meta, 5, &[]);
+	assert!(matches!(res, Err(DecodeError::EarlyEof(_))));
+}
+
+#[test]
+fn dispatch_error_from_extrinsic_failed_event() {
+	let meta = metadata();
+
+	// `System.ExtrinsicFailed(DispatchError::Module { index: 5, error: 1 }, DispatchInfo)`
+	let mut bytes = vec![0u8, 1, 3, 5, 1];
+	bytes.extend(10u64.encode());
+	bytes.extend([0u8, 0]);
+
+	let event = decoder::decode_event(&meta, &mut &*bytes).expect("can decode event");
+	let error = decoder::decode_dispatch_error(&meta, &event.fields[0]).expect("can decode dispatch error");
+
+	let error = error.expect("is a module error");
+	assert_eq!(error.pallet_name, "Balances");
+	assert_eq!(error.ty.name, "LiquidityRestrictions");
+}
+
+#[test]
+fn dispatch_error_with_wrapped_module_error() {
+	let meta = metadata();
+
+	// Newer runtimes: `DispatchError::Module(ModuleError { index, error: [u8; 4] })`.
+	let dispatch_error = Value::unnamed_variant(
+		"Module",
+		[Value::named_composite([
+			("index", Value::u128(5)),
+			("error", Value::unnamed_composite([Value::u128(0), Value::u128(0), Value::u128(0), Value::u128(0)])),
+		])],
+	);
+
+	let error = decoder::decode_dispatch_error(&meta, &dispatch_error).unwrap().expect("is a module error");
+	assert_eq!(error.pallet_name, "Balances");
+	assert_eq!(error.ty.name, "VestingBalance");
+}
+
+#[test]
+fn dispatch_error_that_is_not_a_module_error() {
+	let meta = metadata();
+
+	let dispatch_error = Value::unnamed_variant("BadOrigin", []);
+	assert!(decoder::decode_dispatch_error(&meta, &dispatch_error).unwrap().is_none());
+
+	let not_a_dispatch_error = Value::u128(1);
+	let res = decoder::decode_dispatch_error(&meta, &not_a_dispatch_error);
+	assert!(matches!(res, Err(DecodeError::CannotDecodeDispatchError(_))));
+
+	let bad_index = Value::named_variant("Module", [("index", Value::u128(500)), ("error", Value::u128(0))]);
+	let res = decoder::decode_dispatch_error(&meta, &bad_index);
+	assert!(matches!(res, Err(DecodeError::CannotDecodeDispatchError(_))));
+}
@@ -0,0 +1,133 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+mod common;
+
+use common::metadata;
+use desub_current::{
+	decoder::{self, DecodeError, Phase},
+	Value,
+};
+use parity_scale_codec::{Compact, Encode};
+use sp_core::H256;
+use sp_keyring::AccountKeyring;
+
+fn account_value(keyring: AccountKeyring) -> Value<()> {
+	Value::unnamed_composite(vec![Value::from_bytes(keyring.to_account_id())])
+}
+
+/// `Balances.Transfer(alice, bob, amount)`
+fn transfer_event(amount: u128) -> Vec<u8> {
+	let mut bytes = vec![5u8, 2];
+	bytes.extend(AccountKeyring::Alice.to_account_id().encode());
+	bytes.extend(AccountKeyring::Bob.to_account_id().encode());
+	bytes.extend(amount.encode());
+	bytes
+}
+
+/// `System.ExtrinsicSuccess { weight, class: Normal, pays_fee: Yes }`
+fn extrinsic_success_event(weight: u64) -> Vec<u8> {
+	let mut bytes = vec![0u8, 0];
+	bytes.extend(weight.encode());
+	bytes.extend([0u8, 0]);
+	bytes
+}
+
+#[test]
+fn balance_transfer_event() {
+	let meta = metadata();
+	let bytes = transfer_event(12345);
+
+	let event = decoder::decode_event(&meta, &mut &*bytes).expect("can decode event");
+	let fields: Vec<_> = event.fields.into_iter().map(|v| v.remove_context()).collect();
+
+	assert_eq!(event.pallet_name, "Balances");
+	assert_eq!(&*event.ty.name, "Transfer");
+	assert_eq!(
+		fields,
+		vec![account_value(AccountKeyring::Alice), account_value(AccountKeyring::Bob), Value::u128(12345)]
+	);
+}
+
+#[test]
+fn unknown_event_index() {
+	let meta = metadata();
+	let bytes = vec![5u8, 255];
+
+	let res = decoder::decode_event(&meta, &mut &*bytes);
+	assert!(matches!(res, Err(DecodeError::CannotFindEvent(5, 255))));
+}
+
+#[test]
+fn multiple_event_records() {
+	let meta = metadata();
+	let topic = H256::repeat_byte(0xAB);
+
+	let mut bytes = Compact(3u32).encode();
+	// A transfer in extrinsic 1:
+	bytes.extend(Phase::ApplyExtrinsic(1).encode());
+	bytes.extend(transfer_event(1000));
+	bytes.extend(Vec::<H256>::new().encode());
+	// The success of extrinsic 1, with a topic:
+	bytes.extend(Phase::ApplyExtrinsic(1).encode());
+	bytes.extend(extrinsic_success_event(500));
+	bytes.extend(vec![topic].encode());
+	// Something emitted during finalization:
+	bytes.extend(Phase::Finalization.encode());
+	bytes.extend(extrinsic_success_event(0));
+	bytes.extend(Vec::<H256>::new().encode());
+	// Trailing bytes that aren't part of the events:
+	bytes.extend([1u8, 2, 3]);
+
+	let cursor = &mut &*bytes;
+	let events = decoder::decode_events(&meta, cursor).expect("can decode events");
+
+	assert_eq!(*cursor, &[1u8, 2, 3]);
+	assert_eq!(events.len(), 3);
+
+	assert_eq!(events[0].phase, Phase::ApplyExtrinsic(1));
+	assert_eq!(events[0].event.pallet_name, "Balances");
+	assert_eq!(&*events[0].event.ty.name, "Transfer");
+	assert!(events[0].topics.is_empty());
+
+	assert_eq!(events[1].phase, Phase::ApplyExtrinsic(1));
+	assert_eq!(events[1].event.pallet_name, "System");
+	assert_eq!(&*events[1].event.ty.name, "ExtrinsicSuccess");
+	assert_eq!(events[1].topics, vec![topic]);
+
+	assert_eq!(events[2].phase, Phase::Finalization);
+	assert_eq!(&*events[2].event.ty.name, "ExtrinsicSuccess");
+}
+
+#[test]
+fn partial_events_returned_on_error() {
+	let meta = metadata();
+
+	let mut bytes = Compact(2u32).encode();
+	bytes.extend(Phase::Initialization.encode());
+	bytes.extend(extrinsic_success_event(0));
+	bytes.extend(Vec::<H256>::new().encode());
+	// Second record is truncated part way through the event:
+	bytes.extend(Phase::Initialization.encode());
+	bytes.extend(&transfer_event(1)[..10]);
+
+	let cursor = &mut &*bytes;
+	let (events, _err) = decoder::decode_events(&meta, cursor).expect_err("should fail to decode events");
+
+	// We get back what we managed to decode, and the cursor is left untouched:
+	assert_eq!(events.len(), 1);
+	assert_eq!(cursor.len(), bytes.len());
+}
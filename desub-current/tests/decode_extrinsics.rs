@@ -15,7 +15,7 @@
 // along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
 
 use desub_current::{
-	decoder::{self, SignedExtensionWithAdditional},
+	decoder::{self, DecodeError, ExtrinsicKind, SignedExtensionWithAdditional},
 	Metadata, Value, ValueDef,
 };
 use scale_value::{Composite, Variant};
@@ -288,3 +288,196 @@ fn can_decode_signer_payload() {
 		assert_eq!(additional.remove_context(), expected_additional);
 	}
 }
+
+#[test]
+fn auctions_bid_v5_bare() {
+	let meta = metadata();
+
+	// Auctions.bid as a V5 bare extrinsic.
+	let ext_bytes = &mut &*to_bytes("0x05480104080c1014");
+	let ext = decoder::decode_unwrapped_extrinsic(&meta, ext_bytes).expect("can decode extrinsic");
+
+	assert!(ext_bytes.is_empty(), "No more bytes expected");
+	assert_eq!(ext.version, 5);
+	assert_eq!(ext.kind, ExtrinsicKind::Bare);
+	assert!(ext.signature.is_none());
+	assert!(ext.extensions.is_none());
+	assert_eq!(ext.call_data.pallet_name, "Auctions");
+	assert_eq!(&*ext.call_data.ty.name, "bid");
+}
+
+#[test]
+fn auctions_bid_v5_general() {
+	let meta = metadata();
+
+	// Auctions.bid as a V5 general extrinsic, with extension version 0, an immortal era,
+	// a nonce of 3 and a tip of 0.
+	let ext_bytes = &mut &*to_bytes("0x4500000c00480104080c1014");
+	let ext = decoder::decode_unwrapped_extrinsic(&meta, ext_bytes).expect("can decode extrinsic");
+
+	assert!(ext_bytes.is_empty(), "No more bytes expected");
+	assert_eq!(ext.version, 5);
+	assert_eq!(ext.kind, ExtrinsicKind::General);
+	assert!(ext.signature.is_none());
+	assert_eq!(&*ext.call_data.ty.name, "bid");
+
+	let extensions = ext.extensions.expect("general extrinsics have extensions");
+	assert_eq!(extensions.version, 0);
+	let nonce = extensions.extensions.iter().find(|(name, _)| name == "CheckNonce").expect("has nonce");
+	assert_eq!(nonce.1.clone().remove_context(), singleton_value(Value::u128(3)));
+}
+
+#[test]
+fn signed_extrinsic_kind() {
+	let meta = metadata();
+
+	// Balances.transfer_all (keepalive: false)
+	let ext_bytes = &mut &*to_bytes("0x2d028400d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d01f0431ffe387134b4f84d92d3c3f1ac18c0f42237ad7dbd455bb0cf8a18efb1760528f052b2219ad1601d9a4719e1a446cf307bf6d7e9c56175bfe6e7bf8cbe81450304000504001cbd2d43530a44705ad088af313e18f80b53ef16b36177cd4b77b846f2a5f07c00");
+	let ext = decoder::decode_extrinsic(&meta, ext_bytes).expect("can decode extrinsic");
+
+	assert_eq!(ext.version, 4);
+	assert_eq!(ext.kind, ExtrinsicKind::Signed);
+	assert!(ext.signature.is_some());
+	assert!(ext.extensions.is_none());
+}
+
+#[test]
+fn invalid_extrinsic_type_for_version() {
+	let meta = metadata();
+
+	// V5 extrinsics cannot be signed, and V4 extrinsics cannot be general:
+	for (byte, version, ty) in [(0x85u8, 5, 0b10), (0x44, 4, 0b01)] {
+		let ext_bytes = [byte, 0x48, 0x01, 0x04, 0x08, 0x0c, 0x10, 0x14];
+		let res = decoder::decode_unwrapped_extrinsic(&meta, &mut &ext_bytes[..]);
+		assert!(
+			matches!(res, Err(DecodeError::CannotDecodeExtrinsicType { version: v, ty: t }) if v == version && t == ty)
+		);
+	}
+
+	let res = decoder::decode_unwrapped_extrinsic(&meta, &mut &[0x06u8, 0x48][..]);
+	assert!(matches!(res, Err(DecodeError::CannotDecodeExtrinsicVersion(6))));
+}
+
+#[test]
+fn lenient_decoding_continues_past_failures() {
+	let meta = metadata();
+
+	let mut bytes = to_bytes("0x10");
+	// Auctions.bid:
+	bytes.extend(to_bytes("0x2004480104080c1014"));
+	// An unknown call:
+	bytes.extend(to_bytes("0x0c04ff00"));
+	// Auctions.bid with a trailing byte that isn't consumed:
+	bytes.extend(to_bytes("0x2404480104080c101400"));
+	// System.fill_block:
+	bytes.extend(to_bytes("0x1c040000d2040000"));
+
+	let cursor = &mut &*bytes;
+	let exts = decoder::decode_extrinsics_lenient(&meta, cursor).expect("can iterate extrinsics");
+
+	assert!(cursor.is_empty());
+	assert_eq!(exts.len(), 4);
+	assert_eq!(&*exts[0].as_ref().expect("first is ok").call_data.ty.name, "bid");
+
+	let err = exts[1].as_ref().expect_err("second fails");
+	assert_eq!(err.index, 1);
+	assert_eq!(err.bytes, to_bytes("0x04ff00"));
+	assert!(matches!(err.error, DecodeError::CannotFindCall(255, 0)));
+
+	let err = exts[2].as_ref().expect_err("third fails");
+	assert_eq!(err.index, 2);
+	assert!(matches!(err.error, DecodeError::ExcessBytes(1)));
+
+	assert_eq!(&*exts[3].as_ref().expect("fourth is ok").call_data.ty.name, "fill_block");
+}
+
+#[test]
+fn lenient_decoding_stops_at_bad_length() {
+	let meta = metadata();
+
+	// Two extrinsics are reported, but the second length prefix is bogus:
+	let bytes = to_bytes("0x082004480104080c1014ff");
+	let cursor = &mut &*bytes;
+	let exts = decoder::decode_extrinsics_lenient(&meta, cursor).expect("can iterate extrinsics");
+
+	assert_eq!(exts.len(), 2);
+	assert!(exts[0].is_ok());
+	let err = exts[1].as_ref().expect_err("second fails");
+	assert_eq!(err.index, 1);
+	assert_eq!(err.bytes, vec![0xff]);
+}
+
+#[test]
+fn lenient_decoding_ignores_huge_lengths() {
+	let meta = metadata();
+
+	// Over a billion extrinsics are reported, but only one is present:
+	let bytes = to_bytes("0xfeffffff1c040000d2040000");
+	let cursor = &mut &*bytes;
+	let exts = decoder::decode_extrinsics_lenient(&meta, cursor).expect("can iterate extrinsics");
+
+	assert_eq!(exts.len(), 1);
+	assert!(exts[0].is_ok());
+}
+
+#[test]
+fn extrinsics_record_hash_length_index_and_bytes() {
+	let meta = metadata();
+
+	// Balances.transfer (signed) followed by System.fill_block (unsigned):
+	let transfer = to_bytes("0x31028400d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d016ada9b477ef454972200e098f1186d4a2aeee776f1f6a68609797f5ba052906ad2427bdca865442158d118e2dfc82226077e4dfdff975d005685bab66eefa38a150200000500001cbd2d43530a44705ad088af313e18f80b53ef16b36177cd4b77b846f2a5f07ce5c0");
+	let fill_block = to_bytes("0x1c040000d2040000");
+	let bytes = [to_bytes("0x08"), transfer.clone(), fill_block.clone()].concat();
+
+	let exts = decoder::decode_extrinsics(&meta, &mut &*bytes).unwrap();
+	assert_eq!(exts.len(), 2);
+
+	for (i, (ext, encoded)) in exts.iter().zip([transfer, fill_block]).enumerate() {
+		assert_eq!(ext.index, Some(i));
+		assert_eq!(ext.encoded_len, encoded.len());
+		assert_eq!(ext.hash, sp_core::H256(sp_core::blake2_256(&encoded)));
+		// The raw bytes don't include the length prefix:
+		assert_eq!(ext.bytes, encoded[encoded.len() - ext.bytes.len()..]);
+		assert!(ext.bytes.ends_with(&ext.call_data.bytes));
+	}
+	assert_eq!(exts[0].bytes.len(), 140);
+	assert_eq!(exts[1].call_data.bytes, to_bytes("0x0000d2040000"));
+	assert_eq!(exts[1].call_data.hash(), sp_core::H256(sp_core::blake2_256(&to_bytes("0x0000d2040000"))));
+
+	// Raw bytes are serialized as hex strings:
+	let json = serde_json::to_value(&exts[1]).unwrap();
+	assert_eq!(json["bytes"], "0x040000d2040000");
+	assert_eq!(json["call_data"]["bytes"], "0x0000d2040000");
+
+	// Decoding a single extrinsic on its own gives the same hash, but no index:
+	let single = decoder::decode_unwrapped_extrinsic(&meta, &mut &exts[0].bytes[..]).unwrap();
+	assert_eq!(single.index, None);
+	assert_eq!(single.hash, exts[0].hash);
+}
+
+#[test]
+fn lenient_decoding_records_block_index() {
+	let meta = metadata();
+
+	// Auctions.bid, an unknown call, then System.fill_block:
+	let bytes = to_bytes("0x0c2004480104080c10140c04ff001c040000d2040000");
+	let exts = decoder::decode_extrinsics_lenient(&meta, &mut &*bytes).unwrap();
+
+	assert_eq!(exts[0].as_ref().unwrap().index, Some(0));
+	assert_eq!(exts[2].as_ref().unwrap().index, Some(2));
+	assert_eq!(exts[2].as_ref().unwrap().encoded_len, 8);
+}
+
+#[test]
+fn call_arguments_are_labelled_with_names_and_types() {
+	let meta = metadata();
+
+	// Balances.transfer (amount: 12345)
+	let ext_bytes = &mut &*to_bytes("0x31028400d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d016ada9b477ef454972200e098f1186d4a2aeee776f1f6a68609797f5ba052906ad2427bdca865442158d118e2dfc82226077e4dfdff975d005685bab66eefa38a150200000500001cbd2d43530a44705ad088af313e18f80b53ef16b36177cd4b77b846f2a5f07ce5c0");
+	let ext = decoder::decode_extrinsic(&meta, ext_bytes).expect("can decode extrinsic");
+
+	let args = ext.call_data.labelled_arguments(&meta);
+	let labels: Vec<_> = args.iter().map(|arg| (arg.name, arg.ty.as_str())).collect();
+	assert_eq!(labels, [(Some("dest"), "MultiAddress<AccountId32, ()>"), (Some("value"), "Compact<u128>")]);
+	assert_eq!(args[1].value.clone().remove_context(), Value::u128(12345));
+}
@@ -0,0 +1,146 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+mod common;
+
+use common::{find_type, type_id_by_path, v15_metadata};
+use desub_current::{
+	decoder::{self, DecodeError},
+	scale_info::{form::PortableForm, PortableRegistry, TypeDef, TypeDefPrimitive},
+	Metadata, Value,
+};
+use frame_metadata::v15::{RuntimeApiMetadata, RuntimeApiMethodMetadata, RuntimeApiMethodParamMetadata};
+use parity_scale_codec::Encode;
+use scale_value::{At, Composite, ValueDef};
+use sp_keyring::AccountKeyring;
+
+fn method(name: &str, inputs: &[(&str, u32)], output: u32) -> RuntimeApiMethodMetadata<PortableForm> {
+	RuntimeApiMethodMetadata {
+		name: name.into(),
+		inputs: inputs
+			.iter()
+			.map(|(name, ty)| RuntimeApiMethodParamMetadata { name: (*name).into(), ty: (*ty).into() })
+			.collect(),
+		output: output.into(),
+		docs: vec![],
+	}
+}
+
+fn primitive(types: &PortableRegistry, primitive: TypeDefPrimitive) -> u32 {
+	find_type(types, |ty| ty.type_def == TypeDef::Primitive(primitive.clone()))
+}
+
+// The `Option<Vec<u8>>` returned when asking for metadata.
+fn optional_bytes(types: &PortableRegistry) -> u32 {
+	let u8_ty = primitive(types, TypeDefPrimitive::U8);
+	let bytes = find_type(types, |ty| matches!(&ty.type_def, TypeDef::Sequence(s) if s.type_param.id == u8_ty));
+	find_type(types, |ty| ty.path.segments == ["Option"] && ty.type_params[0].ty.map(|t| t.id) == Some(bytes))
+}
+
+// Build V15 metadata from the V14 polkadot metadata, with a few runtime APIs that use types from its registry.
+fn metadata() -> Metadata {
+	v15_metadata(|v15| {
+		let types = &v15.types;
+		let account_id = type_id_by_path(types, &["sp_core", "crypto", "AccountId32"]);
+		let u32_ty = primitive(types, TypeDefPrimitive::U32);
+		let extrinsic = type_id_by_path(types, &["sp_runtime", "generic", "unchecked_extrinsic", "UncheckedExtrinsic"]);
+		let dispatch_info = type_id_by_path(types, &["frame_support", "weights", "DispatchInfo"]);
+
+		v15.apis = vec![
+			RuntimeApiMetadata {
+				name: "AccountNonceApi".into(),
+				methods: vec![method("account_nonce", &[("account", account_id)], u32_ty)],
+				docs: vec![],
+			},
+			RuntimeApiMetadata {
+				name: "TransactionPaymentApi".into(),
+				methods: vec![method("query_info", &[("uxt", extrinsic), ("len", u32_ty)], dispatch_info)],
+				docs: vec![],
+			},
+			RuntimeApiMetadata {
+				name: "Metadata".into(),
+				methods: vec![method("metadata_at_version", &[("version", u32_ty)], optional_bytes(types))],
+				docs: vec![],
+			},
+		];
+	})
+}
+
+#[test]
+fn decode_account_nonce_call() {
+	let meta = metadata();
+	let alice = AccountKeyring::Alice.to_account_id();
+	let input = alice.encode();
+
+	let cursor = &mut &*input;
+	let call = decoder::decode_runtime_api_call(&meta, "AccountNonceApi_account_nonce", cursor).unwrap();
+	assert!(cursor.is_empty());
+	assert_eq!(call.api_name, "AccountNonceApi");
+	assert_eq!(call.method_name, "account_nonce");
+	assert_eq!(call.inputs.len(), 1);
+	assert_eq!(call.inputs[0].0, "account");
+	assert_eq!(call.inputs[0].1.clone().remove_context(), Value::unnamed_composite(vec![Value::from_bytes(alice)]));
+
+	let output = 5u32.encode();
+	let value = decoder::decode_runtime_api_output(&meta, "AccountNonceApi_account_nonce", &mut &*output).unwrap();
+	assert_eq!(value.remove_context(), Value::u128(5));
+}
+
+#[test]
+fn decode_query_info_call() {
+	let meta = metadata();
+	let uxt = hex::decode("04480104080c1014").unwrap();
+	let input = [uxt.encode(), 100u32.encode()].concat();
+
+	let cursor = &mut &*input;
+	let call = decoder::decode_runtime_api_call(&meta, "TransactionPaymentApi_query_info", cursor).unwrap();
+	assert!(cursor.is_empty());
+	let names: Vec<_> = call.inputs.iter().map(|(name, _)| &**name).collect();
+	assert_eq!(names, vec!["uxt", "len"]);
+	assert_eq!(call.inputs[1].1.clone().remove_context(), Value::u128(100));
+
+	// DispatchInfo { weight: 1000, class: Operational, pays_fee: No }
+	let output = [1000u64.encode(), vec![1, 1]].concat();
+	let value = decoder::decode_runtime_api_output(&meta, "TransactionPaymentApi_query_info", &mut &*output).unwrap();
+	assert_eq!(value.at("weight").unwrap().clone().remove_context(), Value::u128(1000));
+	assert!(matches!(&value.at("class").unwrap().value, ValueDef::Variant(v) if v.name == "Operational"));
+	assert!(matches!(&value.at("pays_fee").unwrap().value, ValueDef::Variant(v) if v.name == "No"));
+	assert!(matches!(value.value, ValueDef::Composite(Composite::Named(_))));
+}
+
+#[test]
+fn decode_metadata_at_version_output() {
+	let meta = metadata();
+	let output = Some(vec![1u8, 2, 3]).encode();
+
+	let value = decoder::decode_runtime_api_output(&meta, "Metadata_metadata_at_version", &mut &*output).unwrap();
+	assert_eq!(value.remove_context(), Value::unnamed_variant("Some", vec![Value::from_bytes([1u8, 2, 3])]));
+}
+
+#[test]
+fn unknown_runtime_api_methods_are_reported() {
+	let meta = metadata();
+
+	for name in ["AccountNonceApi_not_a_method", "NotAnApi_account_nonce", "AccountNonceApi"] {
+		let err = decoder::decode_runtime_api_call(&meta, name, &mut &[][..]).unwrap_err();
+		assert!(matches!(err, DecodeError::CannotFindRuntimeApiMethod(n) if n == name));
+	}
+
+	// V14 metadata doesn't describe any runtime APIs:
+	let v14 = common::metadata();
+	let err = decoder::decode_runtime_api_output(&v14, "AccountNonceApi_account_nonce", &mut &[][..]).unwrap_err();
+	assert!(matches!(err, DecodeError::CannotFindRuntimeApiMethod(_)));
+}
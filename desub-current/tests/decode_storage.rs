@@ -15,7 +15,7 @@
 // along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
 
 use desub_current::{
-	decoder::{self, StorageHasher},
+	decoder::{self, StorageDecodeError, StorageHasher},
 	Metadata, Value,
 };
 use parity_scale_codec::Encode;
@@ -176,3 +176,43 @@ fn imonline_authoredblocks() {
 	let val = decoder::decode_value_by_id(&meta, entry.ty, &mut &*bytes).unwrap();
 	assert_eq!(val.remove_context(), Value::u128(5678));
 }
+
+// Decode the key and value in one go, falling back to the default value if none is given.
+#[test]
+fn timestamp_now_with_value() {
+	let meta = metadata();
+	let storage = decoder::decode_storage(&meta);
+
+	// Timestamp.Now(): u64
+	bytes!(storage_key = "0xf0c365c3cf59d671eb72da0e7a4113c49f1f0515f462cdcf84e0f1d6045dfcbb");
+
+	let bytes = 123u64.encode();
+	let (entry, val) = storage.decode_key_and_value(&meta, &mut &**storage_key, Some(&bytes)).unwrap();
+	assert_eq!(entry.name, "Now");
+	assert_eq!(val.unwrap().remove_context(), Value::u128(123));
+
+	// The entry has a default modifier, so with no value we get back the default:
+	let (_, val) = storage.decode_key_and_value(&meta, &mut &**storage_key, None).unwrap();
+	assert_eq!(val.unwrap().remove_context(), Value::u128(0));
+
+	// Bytes left over after decoding the value is an error:
+	let bytes = [123u64.encode(), vec![1]].concat();
+	let entry = storage.decode_key(&meta, storage_key).unwrap();
+	let err = storage.decode_value(&meta, &entry, Some(&bytes)).unwrap_err();
+	assert!(matches!(err, StorageDecodeError::ExcessValueBytes(1)));
+}
+
+// An optional entry has no value if none is given.
+#[test]
+fn democracy_blacklist_no_value() {
+	let meta = metadata();
+	let storage = decoder::decode_storage(&meta);
+
+	// Democracy.Blacklist([1u8; 32]: H256): ..
+	bytes!(storage_key = "0xf2794c22e353e9a839f12faab03a911bb7612c99e31defd01cd5a28e9967e2080101010101010101010101010101010101010101010101010101010101010101");
+
+	let (entry, val) = storage.decode_key_and_value(&meta, storage_key, None).unwrap();
+	assert!(storage_key.is_empty(), "No more bytes expected");
+	assert_eq!(entry.name, "Blacklist");
+	assert_eq!(val, None);
+}
@@ -0,0 +1,84 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+mod common;
+
+use common::metadata;
+use desub_current::{
+	decoder,
+	encoder::{self, CallEncodeError},
+	Value,
+};
+use sp_keyring::AccountKeyring;
+
+fn account_value(keyring: AccountKeyring) -> Value<()> {
+	Value::unnamed_composite(vec![Value::from_bytes(keyring.to_account_id())])
+}
+
+#[test]
+fn encode_value_roundtrips() {
+	let meta = metadata();
+	let entry = meta.constant("System", "BlockLength").expect("constant exists");
+	let entry_value = entry.value(&meta).expect("can decode constant");
+
+	let bytes = encoder::encode_value_by_id(&meta, entry.ty(), &entry_value).expect("can encode value");
+	assert_eq!(bytes, entry.bytes());
+	let value = decoder::decode_value_by_id(&meta, entry.ty(), &mut &*bytes).expect("can decode value");
+	assert_eq!(value, entry_value);
+}
+
+#[test]
+fn encode_value_type_mismatch() {
+	let meta = metadata();
+	// Type 6 is a u128; a string won't fit:
+	let res = encoder::encode_value_by_id(&meta, 6u32, &Value::string("hello"));
+	assert!(res.is_err());
+}
+
+#[test]
+fn encode_call_data_roundtrips() {
+	let meta = metadata();
+	let dest = Value::unnamed_variant("Id", vec![account_value(AccountKeyring::Bob)]);
+	let args = vec![dest.clone(), Value::u128(12345)];
+
+	let bytes = encoder::encode_call_data(&meta, "Balances", "transfer", &args).expect("can encode call data");
+	let cursor = &mut &*bytes;
+	let call_data = decoder::decode_call_data(&meta, cursor).expect("can decode call data");
+
+	assert!(cursor.is_empty());
+	assert_eq!(call_data.pallet_name, "Balances");
+	assert_eq!(&*call_data.ty.name, "transfer");
+	let decoded: Vec<_> = call_data.arguments.into_iter().map(|v| v.remove_context()).collect();
+	assert_eq!(decoded, args);
+}
+
+#[test]
+fn encode_call_data_errors() {
+	let meta = metadata();
+
+	let res = encoder::encode_call_data(&meta, "NotAPallet", "transfer", &[Value::u128(1)]);
+	assert!(matches!(res, Err(CallEncodeError::PalletNotFound(p)) if p == "NotAPallet"));
+
+	let res = encoder::encode_call_data(&meta, "Balances", "not_a_call", &[Value::u128(1)]);
+	assert!(matches!(res, Err(CallEncodeError::CallNotFound { .. })));
+
+	let res = encoder::encode_call_data(&meta, "Balances", "transfer", &[Value::u128(1)]);
+	assert!(matches!(res, Err(CallEncodeError::WrongNumberOfArguments { expected: 2, got: 1 })));
+
+	let args = [Value::u128(1), Value::u128(2)];
+	let res = encoder::encode_call_data(&meta, "Balances", "transfer", &args);
+	assert!(matches!(res, Err(CallEncodeError::CouldNotEncodeArgument { arg: 0, .. })));
+}
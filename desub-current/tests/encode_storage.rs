@@ -0,0 +1,117 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+mod common;
+
+use common::metadata;
+use desub_current::{
+	decoder::{self, StorageHasher},
+	encoder::{self, StorageEncodeError},
+	Value,
+};
+
+fn account_id_to_value<A: AsRef<[u8]>>(account_id_bytes: A) -> Value<()> {
+	Value::unnamed_composite(vec![Value::from_bytes(account_id_bytes)])
+}
+
+fn to_hex(bytes: Vec<u8>) -> String {
+	format!("0x{}", hex::encode(bytes))
+}
+
+// The same storage keys that we check we can decode should be what we encode.
+
+#[test]
+fn timestamp_now() {
+	let meta = metadata();
+	let storage = encoder::encode_storage(&meta);
+
+	let key = storage.encode_key::<()>(&meta, "Timestamp", "Now", &[]).unwrap();
+	assert_eq!(to_hex(key), "0xf0c365c3cf59d671eb72da0e7a4113c49f1f0515f462cdcf84e0f1d6045dfcbb");
+}
+
+#[test]
+fn democracy_blacklist() {
+	let meta = metadata();
+	let storage = encoder::encode_storage(&meta);
+
+	let hash = Value::unnamed_composite(vec![Value::from_bytes([1u8; 32])]);
+	let key = storage.encode_key(&meta, "Democracy", "Blacklist", &[hash]).unwrap();
+	assert_eq!(to_hex(key), "0xf2794c22e353e9a839f12faab03a911bb7612c99e31defd01cd5a28e9967e2080101010101010101010101010101010101010101010101010101010101010101");
+}
+
+#[test]
+fn balances_account() {
+	let meta = metadata();
+	let storage = encoder::encode_storage(&meta);
+
+	let bob = account_id_to_value(sp_keyring::AccountKeyring::Bob.to_account_id());
+	let key = storage.encode_key(&meta, "Balances", "Account", &[bob]).unwrap();
+	assert_eq!(to_hex(key), "0xc2261276cc9d1f8598ea4b6a74b15c2fb99d880ec681799c0cf30e8886371da94f9aea1afa791265fae359272badc1cf8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48");
+}
+
+#[test]
+fn imonline_authoredblocks() {
+	let meta = metadata();
+	let storage = encoder::encode_storage(&meta);
+
+	let bob = account_id_to_value(sp_keyring::AccountKeyring::Bob.to_account_id());
+	let key = storage.encode_key(&meta, "ImOnline", "AuthoredBlocks", &[Value::u128(1234), bob]).unwrap();
+	assert_eq!(to_hex(key), "0x2b06af9719ac64d755623cda8ddd9b94b1c371ded9e9c565e89ba783c4d5f5f9548491cbfe725727d2040000a647e755c30521d38eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48");
+
+	// Providing only some of the keys gives back a partial key, for iterating over entries:
+	let key = storage.encode_key(&meta, "ImOnline", "AuthoredBlocks", &[Value::u128(1234)]).unwrap();
+	assert_eq!(
+		to_hex(key),
+		"0x2b06af9719ac64d755623cda8ddd9b94b1c371ded9e9c565e89ba783c4d5f5f9548491cbfe725727d2040000"
+	);
+
+	let key = storage.encode_key::<()>(&meta, "ImOnline", "AuthoredBlocks", &[]).unwrap();
+	assert_eq!(to_hex(key), "0x2b06af9719ac64d755623cda8ddd9b94b1c371ded9e9c565e89ba783c4d5f5f9");
+}
+
+// A map with a single hasher can still be keyed by a tuple, which is hashed as one value.
+#[test]
+fn auctions_reservedamounts() {
+	let meta = metadata();
+	let storage = encoder::encode_storage(&meta);
+
+	let bob = account_id_to_value(sp_keyring::AccountKeyring::Bob.to_account_id());
+	let para_id = Value::unnamed_composite(vec![Value::u128(1000)]);
+	let key_value = Value::unnamed_composite(vec![bob, para_id]);
+	let key = storage.encode_key(&meta, "Auctions", "ReservedAmounts", std::slice::from_ref(&key_value)).unwrap();
+	assert_eq!(to_hex(key.clone()), "0xca32a41f4b3ed515863dc0a38697f84e49ddb7db82ffb65f362833fd1102d66d072eae177c54862a8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48e8030000");
+
+	// The decoder should agree on the shape of the key:
+	let entry = decoder::decode_storage(&meta).decode_key(&meta, &mut &*key).unwrap();
+	let keys = entry.details.map_keys();
+	assert_eq!(keys.len(), 1);
+	assert!(matches!(&keys[0].hasher, StorageHasher::Twox64Concat(v) if v.clone().remove_context() == key_value));
+}
+
+#[test]
+fn bad_keys_are_rejected() {
+	let meta = metadata();
+	let storage = encoder::encode_storage(&meta);
+
+	let err = storage.encode_key(&meta, "Timestamp", "Now", &[Value::u128(1)]).unwrap_err();
+	assert!(matches!(err, StorageEncodeError::TooManyKeys { got: 1, max: 0 }));
+
+	let err = storage.encode_key(&meta, "System", "BlockHash", &[Value::string("nope")]).unwrap_err();
+	assert!(matches!(err, StorageEncodeError::CouldNotEncodeKey { key: 0, .. }));
+
+	let err = storage.encode_key::<()>(&meta, "System", "Nope", &[]).unwrap_err();
+	assert!(matches!(err, StorageEncodeError::NameNotFound(_)));
+}
@@ -0,0 +1,147 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+mod common;
+
+use common::{copy_type, pallet, runtime_metadata, to_metadata, variant};
+use frame_metadata::v14::{StorageEntryType, StorageHasher};
+
+#[test]
+fn identical_metadata_has_no_diff() {
+	let meta = to_metadata(runtime_metadata());
+	assert!(meta.diff(&meta).is_empty());
+}
+
+#[test]
+fn pallets_added_removed_and_renamed() {
+	let old = runtime_metadata();
+	let mut new = old.clone();
+
+	pallet(&mut new, "Balances").name = "Money".to_owned();
+	new.pallets.retain(|p| p.name != "Timestamp");
+	let mut added = pallet(&mut new, "Indices").clone();
+	added.name = "Brand".to_owned();
+	added.index = 200;
+	new.pallets.push(added);
+
+	let diff = to_metadata(old).diff(&to_metadata(new));
+
+	assert_eq!(diff.pallets_added, ["Brand"]);
+	assert_eq!(diff.pallets_removed, ["Timestamp"]);
+	assert_eq!(diff.pallets_renamed.len(), 1);
+	assert_eq!((diff.pallets_renamed[0].from.as_str(), diff.pallets_renamed[0].to.as_str()), ("Balances", "Money"));
+	assert!(diff.pallets_changed.is_empty());
+}
+
+#[test]
+fn call_index_shifts_and_argument_changes() {
+	let old = runtime_metadata();
+	let mut new = old.clone();
+	let calls_ty = pallet(&mut new, "Balances").calls.as_ref().unwrap().ty.id;
+
+	// Shift the index of a call:
+	variant(&mut new.types, calls_ty, "transfer").index = 9;
+	// Change the shape of an argument from `Compact<u128>` to `u32`:
+	variant(&mut new.types, calls_ty, "transfer_keep_alive").fields[1].ty = 4u32.into();
+	// Point an argument at a different, but structurally identical, type:
+	let bool_ty = variant(&mut new.types, calls_ty, "transfer_all").fields[1].ty.id;
+	let bool_copy = copy_type(&mut new.types, bool_ty);
+	variant(&mut new.types, calls_ty, "transfer_all").fields[1].ty = bool_copy.into();
+
+	let diff = to_metadata(old).diff(&to_metadata(new));
+
+	assert!(diff.pallets_added.is_empty() && diff.pallets_removed.is_empty());
+	let balances = diff.pallets_changed.iter().find(|p| p.name == "Balances").unwrap();
+	assert_eq!(balances.index, None);
+	assert!(balances.events.is_empty() && balances.errors.is_empty() && balances.storage.is_empty());
+
+	assert_eq!(balances.calls.index_shifts.len(), 1);
+	assert_eq!(balances.calls.index_shifts[0].name, "transfer");
+	assert_eq!((balances.calls.index_shifts[0].from, balances.calls.index_shifts[0].to), (0, 9));
+
+	assert_eq!(balances.calls.field_changes.len(), 1);
+	let change = &balances.calls.field_changes[0];
+	assert_eq!(change.name, "transfer_keep_alive");
+	assert_eq!(change.from[1].ty, "Compact<u128>");
+	assert_eq!(change.to[1].ty, "u32");
+
+	// Calls which contain other calls are decoded differently now too:
+	let utility = diff.pallets_changed.iter().find(|p| p.name == "Utility").unwrap();
+	let batch = utility.calls.field_changes.iter().find(|c| c.name == "batch").unwrap();
+	assert_eq!(batch.from[0].ty, "Vec<Call>");
+	assert_ne!(batch.from[0].structure, batch.to[0].structure);
+}
+
+#[test]
+fn storage_hasher_key_and_value_changes() {
+	let old = runtime_metadata();
+	let mut new = old.clone();
+	let system = pallet(&mut new, "System");
+	let entries = &mut system.storage.as_mut().unwrap().entries;
+
+	let account = entries.iter_mut().find(|e| e.name == "Account").unwrap();
+	let StorageEntryType::Map { hashers, .. } = &mut account.ty else { panic!("expected a map") };
+	hashers[0] = StorageHasher::Twox64Concat;
+
+	let number = entries.iter_mut().find(|e| e.name == "Number").unwrap();
+	number.ty = StorageEntryType::Plain(8u32.into());
+
+	let diff = to_metadata(old).diff(&to_metadata(new));
+
+	assert_eq!(diff.pallets_changed.len(), 1);
+	let storage = &diff.pallets_changed[0].storage;
+	assert!(storage.added.is_empty() && storage.removed.is_empty());
+	assert_eq!(storage.changed.len(), 2);
+
+	let account = storage.changed.iter().find(|c| c.name == "Account").unwrap();
+	let hashers = account.hashers.as_ref().unwrap();
+	assert_eq!(
+		(hashers.from.as_slice(), hashers.to.as_slice()),
+		(&["Blake2_128Concat".to_owned()][..], &["Twox64Concat".to_owned()][..])
+	);
+	assert!(account.keys.is_none() && account.value.is_none());
+
+	let number = storage.changed.iter().find(|c| c.name == "Number").unwrap();
+	let value = number.value.as_ref().unwrap();
+	assert_eq!((value.from.ty.as_str(), value.to.ty.as_str()), ("u32", "u64"));
+}
+
+#[test]
+fn diff_report_is_serialisable() {
+	let old = runtime_metadata();
+	let mut new = old.clone();
+	pallet(&mut new, "Balances").index = 100;
+
+	let diff = to_metadata(old).diff(&to_metadata(new));
+	let json = serde_json::to_value(&diff).unwrap();
+
+	assert_eq!(json["pallets_changed"][0]["name"], "Balances");
+	assert_eq!(json["pallets_changed"][0]["index"], serde_json::json!({ "from": 5, "to": 100 }));
+}
+
+#[test]
+fn pallet_order_does_not_affect_type_structure() {
+	let old = runtime_metadata();
+	let mut new = old.clone();
+	// Types are hashed in the order that pallets appear. Moving Utility first means that its `Vec<Call>`
+	// arguments are seen before the outer call enum is, but that shouldn't change their structure:
+	let utility = new.pallets.iter().position(|p| p.name == "Utility").unwrap();
+	let utility = new.pallets.remove(utility);
+	new.pallets.insert(0, utility);
+
+	let diff = to_metadata(old).diff(&to_metadata(new));
+	assert!(diff.is_empty(), "{diff:?}");
+}
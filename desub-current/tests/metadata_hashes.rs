@@ -0,0 +1,146 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+mod common;
+
+use common::{copy_type, pallet, runtime_metadata, to_metadata, variant};
+use frame_metadata::v14::{StorageEntryType, StorageHasher};
+
+#[test]
+fn hashes_are_stable() {
+	let a = to_metadata(runtime_metadata());
+	let b = to_metadata(runtime_metadata());
+
+	for (pa, pb) in a.pallets().zip(b.pallets()) {
+		assert_eq!(pa.hash(), pb.hash(), "pallet {}", pa.name());
+		for call in pa.calls() {
+			assert_eq!(pa.call_hash(&call.name), pb.call_hash(&call.name));
+		}
+		for (ea, eb) in pa.storage().zip(pb.storage()) {
+			assert_eq!(ea.hash(), eb.hash());
+		}
+	}
+
+	// Different pallets and calls hash differently:
+	let balances = a.pallet("Balances").unwrap();
+	assert_ne!(balances.hash(), a.pallet("System").unwrap().hash());
+	assert_ne!(balances.call_hash("transfer"), balances.call_hash("transfer_keep_alive"));
+	assert_eq!(balances.call_hash("not_a_call"), None);
+	assert_eq!(balances.event_hash("not_an_event"), None);
+}
+
+#[test]
+fn hashes_do_not_depend_on_type_ids() {
+	let old = runtime_metadata();
+	let mut new = old.clone();
+	let calls_ty = pallet(&mut new, "Balances").calls.as_ref().unwrap().ty.id;
+	let events_ty = pallet(&mut new, "Balances").event.as_ref().unwrap().ty.id;
+
+	// Point some fields at copies of their types, which have new IDs:
+	let dest_ty = variant(&mut new.types, calls_ty, "transfer").fields[0].ty.id;
+	let dest_copy = copy_type(&mut new.types, dest_ty);
+	variant(&mut new.types, calls_ty, "transfer").fields[0].ty = dest_copy.into();
+	let amount_ty = variant(&mut new.types, events_ty, "Transfer").fields[2].ty.id;
+	let amount_copy = copy_type(&mut new.types, amount_ty);
+	variant(&mut new.types, events_ty, "Transfer").fields[2].ty = amount_copy.into();
+
+	let (old, new) = (to_metadata(old), to_metadata(new));
+	let (old_balances, new_balances) = (old.pallet("Balances").unwrap(), new.pallet("Balances").unwrap());
+
+	assert_eq!(old_balances.call_hash("transfer"), new_balances.call_hash("transfer"));
+	assert_eq!(old_balances.event_hash("Transfer"), new_balances.event_hash("Transfer"));
+	assert_eq!(old_balances.hash(), new_balances.hash());
+}
+
+#[test]
+fn hashes_change_with_structure() {
+	let old = runtime_metadata();
+	let mut new = old.clone();
+	let calls_ty = pallet(&mut new, "Balances").calls.as_ref().unwrap().ty.id;
+
+	// `Compact<u128>` becomes `u32`:
+	variant(&mut new.types, calls_ty, "transfer").fields[1].ty = 4u32.into();
+	let system = pallet(&mut new, "System");
+	let account = system.storage.as_mut().unwrap().entries.iter_mut().find(|e| e.name == "Account").unwrap();
+	let StorageEntryType::Map { hashers, .. } = &mut account.ty else { panic!("expected a map") };
+	hashers[0] = StorageHasher::Twox64Concat;
+
+	let (old, new) = (to_metadata(old), to_metadata(new));
+	let (old_balances, new_balances) = (old.pallet("Balances").unwrap(), new.pallet("Balances").unwrap());
+
+	assert_ne!(old_balances.call_hash("transfer"), new_balances.call_hash("transfer"));
+	assert_eq!(old_balances.call_hash("transfer_all"), new_balances.call_hash("transfer_all"));
+	assert_eq!(old_balances.event_hash("Transfer"), new_balances.event_hash("Transfer"));
+	assert_ne!(old_balances.hash(), new_balances.hash());
+
+	let (old_system, new_system) = (old.pallet("System").unwrap(), new.pallet("System").unwrap());
+	assert_ne!(
+		old_system.storage_entry("Account").unwrap().hash(),
+		new_system.storage_entry("Account").unwrap().hash()
+	);
+	assert_eq!(old_system.storage_entry("Number").unwrap().hash(), new_system.storage_entry("Number").unwrap().hash());
+	assert_ne!(old_system.hash(), new_system.hash());
+
+	// A pallet that doesn't involve anything that changed keeps its hash:
+	assert_eq!(old.pallet("Timestamp").unwrap().hash(), new.pallet("Timestamp").unwrap().hash());
+}
+
+#[test]
+fn call_and_event_hashes_depend_on_the_pallet() {
+	let old = runtime_metadata();
+	let mut new = old.clone();
+	pallet(&mut new, "Balances").index = 100;
+
+	let (old, new) = (to_metadata(old), to_metadata(new));
+	let (old_balances, new_balances) = (old.pallet("Balances").unwrap(), new.pallet("Balances").unwrap());
+
+	// Calls and events are encoded with the index of their pallet, so they change with it:
+	assert_ne!(old_balances.call_hash("transfer"), new_balances.call_hash("transfer"));
+	assert_ne!(old_balances.event_hash("Transfer"), new_balances.event_hash("Transfer"));
+	assert_eq!(
+		old_balances.storage_entry("Account").unwrap().hash(),
+		new_balances.storage_entry("Account").unwrap().hash()
+	);
+
+	// Pallets with identically shaped calls still hash them differently:
+	let council = old.pallet("Council").unwrap();
+	let technical_committee = old.pallet("TechnicalCommittee").unwrap();
+	assert_ne!(council.call_hash("propose"), technical_committee.call_hash("propose"));
+}
+
+#[test]
+fn pallet_hashes_match_individual_hashes() {
+	let meta = to_metadata(runtime_metadata());
+	let hashes = meta.pallet_hashes();
+	assert_eq!(hashes.len(), meta.pallets().count());
+
+	for (pallet, hashes) in meta.pallets().zip(&hashes) {
+		assert_eq!(hashes.name, pallet.name());
+		assert_eq!(hashes.hash, pallet.hash(), "{}", pallet.name());
+		assert_eq!(hashes.calls.len(), pallet.calls().len());
+		for (name, hash) in &hashes.calls {
+			assert_eq!(Some(*hash), pallet.call_hash(name), "{}.{name}", pallet.name());
+		}
+		assert_eq!(hashes.events.len(), pallet.events().len());
+		for (name, hash) in &hashes.events {
+			assert_eq!(Some(*hash), pallet.event_hash(name), "{}.{name}", pallet.name());
+		}
+		assert_eq!(hashes.storage.len(), pallet.storage().count());
+		for (name, hash) in &hashes.storage {
+			assert_eq!(*hash, pallet.storage_entry(name).unwrap().hash(), "{}.{name}", pallet.name());
+		}
+	}
+}
@@ -0,0 +1,123 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+mod common;
+
+use common::metadata;
+use desub_current::{
+	metadata::{StorageEntryModifier, StorageHasher},
+	Value,
+};
+
+#[test]
+fn iterate_pallets_in_order() {
+	let meta = metadata();
+
+	let pallets: Vec<_> = meta.pallets().map(|p| (p.index(), p.name())).take(7).collect();
+	assert_eq!(
+		pallets,
+		vec![
+			(0, "System"),
+			(1, "Scheduler"),
+			(2, "Babe"),
+			(3, "Timestamp"),
+			(4, "Indices"),
+			(5, "Balances"),
+			(32, "TransactionPayment")
+		]
+	);
+
+	assert_eq!(meta.pallet("Balances").unwrap().index(), 5);
+	assert_eq!(meta.pallet_by_index(32).unwrap().name(), "TransactionPayment");
+	assert!(meta.pallet("NotAPallet").is_none());
+	assert!(meta.pallet_by_index(255).is_none());
+}
+
+#[test]
+fn inspect_calls_events_and_errors() {
+	let meta = metadata();
+	let balances = meta.pallet("Balances").unwrap();
+
+	let transfer = balances.call("transfer").expect("transfer call exists");
+	assert_eq!(transfer.index, 0);
+	let args: Vec<_> = transfer.fields.iter().map(|f| (f.name.as_deref(), f.type_name.as_deref())).collect();
+	assert_eq!(
+		args,
+		vec![(Some("dest"), Some("<T::Lookup as StaticLookup>::Source")), (Some("value"), Some("T::Balance"))]
+	);
+	assert!(!transfer.docs.is_empty());
+
+	let event = balances.event("Transfer").expect("Transfer event exists");
+	assert_eq!(event.index, 2);
+	assert_eq!(event.fields.len(), 3);
+
+	let error = balances.error("InsufficientBalance").expect("InsufficientBalance error exists");
+	assert_eq!(error.index, 2);
+
+	// Not every pallet has calls/events/errors:
+	let transaction_payment = meta.pallet("TransactionPayment").unwrap();
+	assert!(transaction_payment.calls().is_empty());
+	assert!(transaction_payment.events().is_empty());
+	assert!(transaction_payment.errors().is_empty());
+	assert_eq!(transaction_payment.calls_type_id(), None);
+	assert!(balances.calls_type_id().is_some());
+}
+
+#[test]
+fn inspect_storage_entries() {
+	let meta = metadata();
+
+	let number = meta.pallet("System").unwrap().storage_entry("Number").expect("System.Number exists");
+	assert_eq!(number.prefix(), "System");
+	assert!(matches!(number.modifier(), StorageEntryModifier::Default));
+	assert!(number.hashers().is_empty());
+	assert!(number.key_tys().is_empty());
+	assert_eq!(number.value_ty(), 4);
+	assert_eq!(number.default_bytes(), &[0, 0, 0, 0]);
+
+	let account = meta.pallet("System").unwrap().storage_entry("Account").expect("System.Account exists");
+	assert!(matches!(account.hashers(), [StorageHasher::Blake2_128Concat]));
+	assert_eq!(account.key_tys(), vec![0]);
+
+	// Multiple keys are split out of the key tuple:
+	let stakers = meta.pallet("Staking").unwrap().storage_entry("ErasStakers").expect("Staking.ErasStakers exists");
+	assert!(matches!(stakers.hashers(), [StorageHasher::Twox64Concat, StorageHasher::Twox64Concat]));
+	assert_eq!(stakers.key_tys(), vec![4, 0]);
+
+	// Pallets without storage have no entries:
+	assert_eq!(meta.pallet("Historical").unwrap().storage().count(), 0);
+	assert!(meta.pallet("Balances").unwrap().storage().any(|e| e.name() == "TotalIssuance"));
+}
+
+#[test]
+fn inspect_constants() {
+	let meta = metadata();
+	let balances = meta.pallet("Balances").unwrap();
+
+	assert_eq!(balances.constants().count(), 3);
+	assert!(balances.constants().all(|c| c.pallet() == "Balances"));
+	let existential_deposit = balances.constant("ExistentialDeposit").expect("constant exists");
+	assert_eq!(existential_deposit.value(&meta).unwrap().remove_context(), Value::u128(10_000_000_000));
+}
+
+#[test]
+fn resolve_types_via_the_registry() {
+	let meta = metadata();
+	let transfer = meta.pallet("Balances").unwrap().call("transfer").unwrap();
+
+	let dest_ty = meta.types().resolve(transfer.fields[0].ty.id).expect("type exists");
+	assert_eq!(dest_ty.path.segments, vec!["sp_runtime", "multiaddress", "MultiAddress"]);
+}
@@ -0,0 +1,124 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+mod common;
+
+use common::{find_type, metadata as v14_metadata, type_id_by_path, v15_metadata};
+use desub_current::{
+	decoder,
+	scale_info::{form::PortableForm, Type, TypeDef, TypeDefPrimitive},
+	Metadata, Value,
+};
+use frame_metadata::v15::{
+	CustomValueMetadata, RuntimeApiMetadata, RuntimeApiMethodMetadata, RuntimeApiMethodParamMetadata,
+};
+use parity_scale_codec::Encode;
+
+fn to_bytes(hex_str: &str) -> Vec<u8> {
+	let hex_str = hex_str.strip_prefix("0x").expect("0x should prefix hex encoded bytes");
+	hex::decode(hex_str).expect("valid bytes from hex")
+}
+
+fn is_u32(ty: &Type<PortableForm>) -> bool {
+	matches!(ty.type_def, TypeDef::Primitive(TypeDefPrimitive::U32))
+}
+
+// Build some V15 metadata from the V14 polkadot metadata, adding some made up V15 specific details.
+fn v15_with_details() -> Metadata {
+	v15_metadata(|v15| {
+		let account_id = type_id_by_path(&v15.types, &["sp_core", "crypto", "AccountId32"]);
+		let u32_ty = find_type(&v15.types, is_u32);
+
+		for p in &mut v15.pallets {
+			p.docs = vec![format!("Docs for {}", p.name)];
+		}
+		v15.apis = vec![RuntimeApiMetadata {
+			name: "AccountNonceApi".into(),
+			methods: vec![RuntimeApiMethodMetadata {
+				name: "account_nonce".into(),
+				inputs: vec![RuntimeApiMethodParamMetadata { name: "account".into(), ty: account_id.into() }],
+				output: u32_ty.into(),
+				docs: vec!["Get current account nonce of given `AccountId`.".into()],
+			}],
+			docs: vec!["The API to query account nonce.".into()],
+		}];
+		v15.custom.map.insert("answer".to_owned(), CustomValueMetadata { ty: u32_ty.into(), value: 42u32.encode() });
+	})
+}
+
+#[test]
+fn reports_metadata_version() {
+	assert_eq!(v14_metadata().version(), 14);
+	assert_eq!(v15_with_details().version(), 15);
+}
+
+#[test]
+fn v15_decodes_extrinsics_like_v14() {
+	let (v14, v15) = (v14_metadata(), v15_with_details());
+
+	assert_eq!(v15.extrinsic().address_type_id(), v14.extrinsic().address_type_id());
+	assert_eq!(v15.extrinsic().signature_type_id(), v14.extrinsic().signature_type_id());
+	assert_eq!(v15.extrinsic().call_type_id(), v14.extrinsic().call_type_id());
+
+	// Balances.transfer (signed)
+	let bytes = to_bytes("0x31028400d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d016ada9b477ef454972200e098f1186d4a2aeee776f1f6a68609797f5ba052906ad2427bdca865442158d118e2dfc82226077e4dfdff975d005685bab66eefa38a150200000500001cbd2d43530a44705ad088af313e18f80b53ef16b36177cd4b77b846f2a5f07ce5c0");
+	let from_v14 = decoder::decode_extrinsic(&v14, &mut &*bytes).unwrap();
+	let from_v15 = decoder::decode_extrinsic(&v15, &mut &*bytes).unwrap();
+	assert_eq!(from_v14, from_v15);
+
+	assert_eq!(v15.constants().count(), v14.constants().count());
+	assert_eq!(
+		v15.constant("Balances", "ExistentialDeposit").unwrap().value(&v15).unwrap(),
+		v14.constant("Balances", "ExistentialDeposit").unwrap().value(&v14).unwrap()
+	);
+}
+
+#[test]
+fn v15_details_are_exposed() {
+	let meta = v15_with_details();
+
+	assert_eq!(meta.pallet_docs("Balances"), Some(&["Docs for Balances".to_owned()][..]));
+	assert_eq!(meta.pallet_docs("NotAPallet"), None);
+
+	let outer_enums = meta.outer_enums().expect("V15 has outer enums");
+	assert_eq!(Some(outer_enums.call_enum_ty()), meta.extrinsic().call_type_id());
+	assert_eq!(outer_enums.event_enum_ty(), type_id_by_path(meta.types(), &["polkadot_runtime", "Event"]));
+	assert_eq!(Some(outer_enums.error_enum_ty()), meta.pallet("System").unwrap().errors_type_id());
+
+	assert_eq!(meta.runtime_apis().count(), 1);
+	let api = meta.runtime_api("AccountNonceApi").expect("api exists");
+	assert_eq!(api.docs(), ["The API to query account nonce."]);
+	let method = api.method("account_nonce").expect("method exists");
+	assert_eq!(method.inputs().len(), 1);
+	assert_eq!(method.inputs()[0].name(), "account");
+	assert_eq!(method.inputs()[0].ty(), type_id_by_path(meta.types(), &["sp_core", "crypto", "AccountId32"]));
+	assert_eq!(method.output(), find_type(meta.types(), is_u32));
+	assert!(api.method("not_a_method").is_none());
+
+	let answer = meta.custom_value("answer").expect("custom value exists");
+	let value = decoder::decode_value_by_id(&meta, answer.ty(), &mut answer.bytes()).unwrap();
+	assert_eq!(value.remove_context(), Value::u128(42));
+}
+
+#[test]
+fn v14_has_no_v15_details() {
+	let meta = v14_metadata();
+
+	assert_eq!(meta.pallet_docs("Balances"), None);
+	assert!(meta.outer_enums().is_none());
+	assert_eq!(meta.runtime_apis().count(), 0);
+	assert!(meta.custom_value("answer").is_none());
+}
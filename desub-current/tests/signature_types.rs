@@ -0,0 +1,118 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+mod common;
+
+use common::metadata;
+use desub_current::{
+	decoder::{self, Address, Signature},
+	Metadata, Value,
+};
+use frame_metadata::{
+	v14::{ExtrinsicMetadata, PalletCallMetadata, PalletMetadata, RuntimeMetadataV14},
+	RuntimeMetadata,
+};
+use parity_scale_codec::Encode;
+use scale_info::{meta_type, TypeInfo};
+use std::marker::PhantomData;
+
+fn to_bytes(hex_str: &str) -> Vec<u8> {
+	let hex_str = hex_str.strip_prefix("0x").expect("0x should prefix hex encoded bytes");
+	hex::decode(hex_str).expect("valid bytes from hex")
+}
+
+// The types below mimic those of an Ethereum style chain, which uses 20 byte account IDs
+// and 65 byte ECDSA signatures rather than `MultiAddress` and `MultiSignature`.
+
+#[allow(unused)]
+#[derive(TypeInfo)]
+struct UncheckedExtrinsic<Address, Call, Signature, Extra>(PhantomData<(Address, Call, Signature, Extra)>);
+
+#[derive(TypeInfo, Encode)]
+struct AccountId20([u8; 20]);
+
+#[derive(TypeInfo, Encode)]
+struct EthereumSignature([u8; 65]);
+
+#[allow(non_camel_case_types, unused)]
+#[derive(TypeInfo, Encode)]
+enum Call {
+	remark { remark: Vec<u8> },
+}
+
+fn ethereum_style_metadata() -> Metadata {
+	let pallets = vec![PalletMetadata {
+		name: "System",
+		storage: None,
+		calls: Some(PalletCallMetadata { ty: meta_type::<Call>() }),
+		event: None,
+		constants: vec![],
+		error: None,
+		index: 0,
+	}];
+	let extrinsic = ExtrinsicMetadata {
+		ty: meta_type::<UncheckedExtrinsic<AccountId20, Call, EthereumSignature, ()>>(),
+		version: 4,
+		signed_extensions: vec![],
+	};
+	let metadata = RuntimeMetadataV14::new(pallets, extrinsic, meta_type::<()>());
+	Metadata::from_runtime_metadata(RuntimeMetadata::V14(metadata)).expect("valid metadata")
+}
+
+#[test]
+fn ethereum_style_signed_extrinsic() {
+	let meta = ethereum_style_metadata();
+
+	let mut ext_bytes = vec![0x84];
+	ext_bytes.extend(AccountId20([1; 20]).encode());
+	ext_bytes.extend(EthereumSignature([2; 65]).encode());
+	// The pallet index, followed by the call:
+	ext_bytes.push(0);
+	ext_bytes.extend(Call::remark { remark: b"hello".to_vec() }.encode());
+
+	let cursor = &mut &*ext_bytes;
+	let ext = decoder::decode_unwrapped_extrinsic(&meta, cursor).expect("can decode extrinsic");
+	assert!(cursor.is_empty(), "No more bytes expected");
+	assert_eq!(&*ext.call_data.ty.name, "remark");
+
+	let signature = ext.signature.expect("extrinsic is signed");
+	let address = match signature.address {
+		Address::Value(value) => value.remove_context(),
+		other => panic!("Expected a Value address, got {:?}", other),
+	};
+	assert_eq!(address, Value::unnamed_composite(vec![Value::from_bytes([1; 20])]));
+
+	let signature = match signature.signature {
+		Signature::Value(value) => value.remove_context(),
+		other => panic!("Expected a Value signature, got {:?}", other),
+	};
+	assert_eq!(signature, Value::unnamed_composite(vec![Value::from_bytes([2; 65])]));
+}
+
+#[test]
+fn polkadot_signed_extrinsic_uses_typed_address_and_signature() {
+	let meta = metadata();
+	assert!(meta.extrinsic().address_type_id().is_some());
+	assert!(meta.extrinsic().signature_type_id().is_some());
+
+	// Balances.transfer_all (keepalive: false)
+	let ext_bytes = &mut &*to_bytes("0x2d028400d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d01f0431ffe387134b4f84d92d3c3f1ac18c0f42237ad7dbd455bb0cf8a18efb1760528f052b2219ad1601d9a4719e1a446cf307bf6d7e9c56175bfe6e7bf8cbe81450304000504001cbd2d43530a44705ad088af313e18f80b53ef16b36177cd4b77b846f2a5f07c00");
+	let ext = decoder::decode_extrinsic(&meta, ext_bytes).expect("can decode extrinsic");
+
+	let signature = ext.signature.expect("extrinsic is signed");
+	assert!(signature.address.as_multi_address().is_some());
+	assert!(signature.signature.as_multi_signature().is_some());
+}
@@ -0,0 +1,59 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+mod common;
+
+use common::metadata;
+
+#[test]
+fn primitive_and_anonymous_types() {
+	let meta = metadata();
+
+	assert_eq!(meta.type_signature(2u32).unwrap(), "u8");
+	assert_eq!(meta.type_signature(4u32).unwrap(), "u32");
+	assert_eq!(meta.type_signature(1u32).unwrap(), "[u8; 32]");
+	assert_eq!(meta.type_signature(10u32).unwrap(), "Vec<u8>");
+	assert_eq!(meta.type_signature(396u32).unwrap(), "(u32, AccountId32)");
+}
+
+#[test]
+fn named_types_are_shortened_and_show_generic_params() {
+	let meta = metadata();
+
+	assert_eq!(meta.type_signature(0u32).unwrap(), "AccountId32");
+	assert_eq!(meta.type_signature(30u32).unwrap(), "Option<Vec<u8>>");
+	assert_eq!(meta.type_signature(147u32).unwrap(), "MultiAddress<AccountId32, ()>");
+	assert_eq!(meta.type_signature(48u32).unwrap(), "Vec<(AccountId32, Exposure<AccountId32, u128>)>");
+	// Parameters which aren't described in the registry (like the bound of a `BoundedVec`) are omitted:
+	assert_eq!(meta.type_signature(207u32).unwrap(), "BoundedVec<(Data, Data)>");
+}
+
+#[test]
+fn compact_and_nested_tuple_types() {
+	let meta = metadata();
+
+	assert_eq!(meta.type_signature(254u32).unwrap(), "Vec<(Compact<u32>, Compact<u16>)>");
+	assert_eq!(
+		meta.type_signature(262u32).unwrap(),
+		"Vec<(Compact<u32>, [(Compact<u16>, Compact<PerU16>); 2], Compact<u16>)>"
+	);
+}
+
+#[test]
+fn unknown_type_ids() {
+	let meta = metadata();
+	assert_eq!(meta.type_signature(100_000u32), None);
+}
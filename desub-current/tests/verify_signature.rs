@@ -0,0 +1,160 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+mod common;
+
+use common::metadata;
+use desub_current::{
+	decoder, encoder,
+	verify::{self, AdditionalSigned, VerifyError},
+	Metadata, Value,
+};
+use parity_scale_codec::Encode;
+use sp_core::{blake2_256, ecdsa, Pair, H256};
+use sp_keyring::{AccountKeyring, Ed25519Keyring};
+use sp_runtime::{generic::Era, AccountId32, MultiAddress, MultiSignature};
+
+fn additional_signed() -> AdditionalSigned {
+	AdditionalSigned {
+		spec_version: 9110,
+		transaction_version: 5,
+		genesis_hash: H256::repeat_byte(0x91),
+		block_hash: Some(H256::repeat_byte(0xbb)),
+		other: vec![],
+	}
+}
+
+fn remark(meta: &Metadata, len: usize) -> Vec<u8> {
+	encoder::encode_call_data(meta, "System", "remark", &[Value::from_bytes(vec![7u8; len])]).unwrap()
+}
+
+// Build a signed extrinsic (without the length prefix) the way a wallet would. The signed extensions
+// with data are CheckMortality, CheckNonce and ChargeTransactionPayment; nonce and tip are both 0 here.
+fn signed_extrinsic(
+	call: &[u8],
+	era: Era,
+	additional: &AdditionalSigned,
+	sign: impl Fn(&[u8]) -> (AccountId32, MultiSignature),
+) -> Vec<u8> {
+	let extensions = [era.encode(), vec![0, 0]].concat();
+	let era_hash = if era.is_immortal() { additional.genesis_hash } else { additional.block_hash.unwrap() };
+	let mut payload = [
+		call.to_vec(),
+		extensions.clone(),
+		additional.spec_version.encode(),
+		additional.transaction_version.encode(),
+		additional.genesis_hash.encode(),
+		era_hash.encode(),
+	]
+	.concat();
+	if payload.len() > 256 {
+		payload = blake2_256(&payload).to_vec();
+	}
+
+	let (account, signature) = sign(&payload);
+	[vec![0x84], MultiAddress::<AccountId32, u32>::Id(account).encode(), signature.encode(), extensions, call.to_vec()]
+		.concat()
+}
+
+fn sign_sr25519(payload: &[u8]) -> (AccountId32, MultiSignature) {
+	let pair = AccountKeyring::Alice.pair();
+	(pair.public().into(), pair.sign(payload).into())
+}
+
+fn sign_ed25519(payload: &[u8]) -> (AccountId32, MultiSignature) {
+	let pair = Ed25519Keyring::Bob.pair();
+	(pair.public().into(), pair.sign(payload).into())
+}
+
+fn sign_ecdsa(payload: &[u8]) -> (AccountId32, MultiSignature) {
+	let pair = ecdsa::Pair::from_string("//Charlie", None).unwrap();
+	(AccountId32::new(blake2_256(pair.public().as_ref())), pair.sign(payload).into())
+}
+
+#[test]
+fn verifies_sr25519_signature() {
+	let meta = metadata();
+	let additional = additional_signed();
+	let bytes = signed_extrinsic(&remark(&meta, 10), Era::Immortal, &additional, sign_sr25519);
+
+	let ext = decoder::decode_unwrapped_extrinsic(&meta, &mut &*bytes).unwrap();
+	assert!(verify::verify_signature(&meta, &ext, &additional).unwrap());
+
+	// Small payloads are signed as-is, using the signed extension bytes from the extrinsic:
+	let payload = verify::signer_payload(&meta, &ext, &additional).unwrap();
+	let extension_bytes = &ext.signature.as_ref().unwrap().extension_bytes;
+	assert_eq!(extension_bytes, &[Era::Immortal.encode(), vec![0, 0]].concat());
+	assert!(payload.starts_with(&[ext.call_data.bytes.clone(), extension_bytes.clone()].concat()));
+	assert!(payload.len() <= verify::MAX_UNHASHED_PAYLOAD_LEN);
+}
+
+#[test]
+fn verifies_ed25519_signature_with_mortal_era() {
+	let meta = metadata();
+	let additional = additional_signed();
+	let bytes = signed_extrinsic(&remark(&meta, 10), Era::mortal(64, 1000), &additional, sign_ed25519);
+
+	let ext = decoder::decode_unwrapped_extrinsic(&meta, &mut &*bytes).unwrap();
+	assert!(verify::verify_signature(&meta, &ext, &additional).unwrap());
+
+	// Mortal extrinsics need the block hash that their era begins at:
+	let no_block_hash = AdditionalSigned { block_hash: None, ..additional_signed() };
+	let err = verify::verify_signature(&meta, &ext, &no_block_hash).unwrap_err();
+	assert!(matches!(err, VerifyError::MissingAdditionalSigned(name) if name == "CheckMortality"));
+}
+
+#[test]
+fn verifies_ecdsa_signature_over_hashed_payload() {
+	let meta = metadata();
+	let additional = additional_signed();
+	let bytes = signed_extrinsic(&remark(&meta, 300), Era::Immortal, &additional, sign_ecdsa);
+
+	let ext = decoder::decode_unwrapped_extrinsic(&meta, &mut &*bytes).unwrap();
+	assert!(verify::verify_signature(&meta, &ext, &additional).unwrap());
+
+	// Large payloads are hashed before being signed:
+	let payload = verify::signer_payload(&meta, &ext, &additional).unwrap();
+	assert_eq!(payload.len(), 32);
+}
+
+#[test]
+fn detects_tampered_extrinsics() {
+	let meta = metadata();
+	let additional = additional_signed();
+	let mut bytes = signed_extrinsic(&remark(&meta, 10), Era::Immortal, &additional, sign_sr25519);
+
+	// Changing the additional signed data invalidates the signature:
+	let ext = decoder::decode_unwrapped_extrinsic(&meta, &mut &*bytes).unwrap();
+	let wrong_genesis = AdditionalSigned { genesis_hash: H256::zero(), ..additional_signed() };
+	assert!(!verify::verify_signature(&meta, &ext, &wrong_genesis).unwrap());
+	let wrong_spec = AdditionalSigned { spec_version: 9111, ..additional_signed() };
+	assert!(!verify::verify_signature(&meta, &ext, &wrong_spec).unwrap());
+
+	// So does changing the remark:
+	*bytes.last_mut().unwrap() = 8;
+	let ext = decoder::decode_unwrapped_extrinsic(&meta, &mut &*bytes).unwrap();
+	assert!(!verify::verify_signature(&meta, &ext, &additional).unwrap());
+}
+
+#[test]
+fn cannot_verify_unsigned_extrinsics() {
+	let meta = metadata();
+	let bytes = [vec![0x04], remark(&meta, 10)].concat();
+
+	let ext = decoder::decode_unwrapped_extrinsic(&meta, &mut &*bytes).unwrap();
+	let err = verify::verify_signature(&meta, &ext, &additional_signed()).unwrap_err();
+	assert!(matches!(err, VerifyError::NotSigned));
+}
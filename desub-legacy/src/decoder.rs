@@ -23,10 +23,12 @@
 //! Theoretically, one could upload the deserialized decoder JSON to distribute
 //! to different applications that need the type data
 
+mod events;
 mod extrinsics;
 pub mod metadata;
 mod storage;
 
+pub use self::events::{GenericEvent, Phase};
 pub use self::extrinsics::{ExtrinsicArgument, GenericCall, GenericExtrinsic, GenericSignature};
 pub use self::storage::{GenericStorage, StorageInfo, StorageKey, StorageKeyData, StorageLookupTable, StorageValue};
 
@@ -34,8 +36,8 @@ pub use self::storage::{GenericStorage, StorageInfo, StorageKey, StorageKeyData,
 pub use self::metadata::test_suite;
 
 pub use self::metadata::{
-	CallMetadata, Error as MetadataError, Metadata, ModuleIndex, ModuleMetadata, StorageEntryModifier, StorageHasher,
-	StorageType,
+	CallMetadata, Error as MetadataError, Metadata, ModuleErrorMetadata, ModuleIndex, ModuleMetadata,
+	StorageEntryModifier, StorageHasher, StorageType,
 };
 pub use frame_metadata::v14::StorageEntryType;
 
@@ -211,18 +213,19 @@ impl<'a> DecodeState<'a> {
 
 	/// Interprets the version at the current byte offset.
 	/// Returns whether the extrinsic is signed.
-	fn interpret_version(&self) -> bool {
-		let version = self.do_index();
+	fn interpret_version(&self) -> Result<bool, Error> {
+		let version = self.do_index()?;
 		let is_signed = version & 0b1000_0000 != 0;
 		let version = version & 0b0111_1111;
 		log::trace!("Extrinsic Version: {}", version);
-		is_signed
+		Ok(is_signed)
 	}
 
 	/// Get the scale length at the current point in time.
 	/// Increment cursor accordingly to the length.
 	fn scale_length(&mut self) -> Result<usize, Error> {
-		let length = Decoder::scale_length(&self.data[self.cursor.load(Ordering::Relaxed)..])?;
+		let cursor = self.cursor();
+		let length = Decoder::scale_length(self.data.get(cursor..).ok_or(Error::UnexpectedEnd(cursor))?)?;
 		log::trace!("Scale Byte Length {}, actual items: {}", length.1, length.0);
 		self.cursor.fetch_add(length.1, Ordering::Relaxed);
 		Ok(length.0)
@@ -243,10 +246,12 @@ impl<'a> DecodeState<'a> {
 
 	/// Current value at cursor (data\[cursor\]).
 	/// Increment the cursor by 1.
-	fn do_index(&self) -> u8 {
-		let number = self.data[self.cursor.load(Ordering::Relaxed)];
+	/// Errors if the cursor is past the end of the data.
+	fn do_index(&self) -> Result<u8, Error> {
+		let cursor = self.cursor();
+		let number = *self.data.get(cursor).ok_or(Error::UnexpectedEnd(cursor))?;
 		self.add(1);
-		number
+		Ok(number)
 	}
 
 	/// Decode a value, automatically incrementing `cursor`
@@ -281,11 +286,11 @@ impl<'a> DecodeState<'a> {
 	fn observe(&self, line: u32) {
 		let module = self.module.name();
 		let cursor = self.cursor.load(Ordering::Relaxed);
-		let value_at_cursor = &self.data[cursor];
-		let data_at_cursor = &self.data[cursor..];
+		let value_at_cursor = self.data.get(cursor);
+		let data_at_cursor = self.data.get(cursor..).unwrap_or_default();
 
 		log::trace!(
-			"line: {}, module = {}, call = {:?}, cursor = {}, data[cursor] = {}, data[cursor..] = {:?}",
+			"line: {}, module = {}, call = {:?}, cursor = {}, data[cursor] = {:?}, data[cursor..] = {:?}",
 			line,
 			module,
 			self.call.borrow().as_ref().map(|c| c.name()),
@@ -344,45 +349,75 @@ impl Decoder {
 		self.versions.get(&version)
 	}
 
-	fn decode_key_len(&self, key: &[u8], hasher: &StorageHasher) -> Vec<u8> {
-		match hasher {
-			StorageHasher::Blake2_128 | StorageHasher::Twox128 | StorageHasher::Blake2_128Concat => key[..16].to_vec(),
-			StorageHasher::Blake2_256 | StorageHasher::Twox256 => key[..32].to_vec(),
-			StorageHasher::Twox64Concat => key[..8].to_vec(),
-			StorageHasher::Identity => todo!(),
+	/// Decode a single hashed storage map key at the current cursor, returning its bytes
+	/// and, for hashers that keep the plain key around (`Blake2_128Concat`, `Twox64Concat` and
+	/// `Identity`), the decoded key itself.
+	fn decode_map_key(
+		&self,
+		state: &mut DecodeState,
+		hasher: &StorageHasher,
+		key_type: &RustTypeMarker,
+	) -> Result<(Vec<u8>, Option<SubstrateType>), Error> {
+		let start = state.cursor();
+		let hash_len = match hasher {
+			StorageHasher::Blake2_128 | StorageHasher::Twox128 | StorageHasher::Blake2_128Concat => 16,
+			StorageHasher::Blake2_256 | StorageHasher::Twox256 => 32,
+			StorageHasher::Twox64Concat => 8,
+			StorageHasher::Identity => 0,
+		};
+		if start + hash_len > state.data.len() {
+			return Err(Error::StorageKeyTooShort { expected: start + hash_len, found: state.data.len() });
 		}
+		state.add(hash_len);
+		let value = match hasher {
+			StorageHasher::Blake2_128Concat | StorageHasher::Twox64Concat | StorageHasher::Identity => {
+				Some(self.decode_single(state, key_type, false)?)
+			}
+			_ => None,
+		};
+		Ok((state.data[start..state.cursor()].to_vec(), value))
 	}
 
-	fn get_key_data(&self, key: &[u8], info: &StorageInfo, lookup_table: &StorageLookupTable) -> StorageKey {
-		let key = if let Some(k) = lookup_table.extra_key_data(key) {
-			k
+	fn get_key_data(
+		&self,
+		key: &[u8],
+		info: &StorageInfo,
+		lookup_table: &StorageLookupTable,
+		meta: &Metadata,
+		spec: SpecVersion,
+	) -> Result<StorageKey, Error> {
+		let prefix_len = if let Some(k) = lookup_table.extra_key_data(key) {
+			key.len() - k.len()
 		} else {
-			return StorageKey {
+			return Ok(StorageKey {
 				module: info.module.name().into(),
 				prefix: info.meta.prefix().to_string(),
 				extra: None,
-			};
+			});
 		};
+		// Start after the prefix, so that any offsets we report are relative to the whole key.
+		let mut state = DecodeState::new(Some(&info.module), None, meta, prefix_len, spec, key);
 
-		match &info.meta.ty {
+		let storage_key = match &info.meta.ty {
 			StorageType::Plain(_) => {
 				StorageKey { module: info.module.name().into(), prefix: info.meta.prefix().to_string(), extra: None }
 			}
 			StorageType::Map { hasher, key: key_type, .. } => {
-				let key = self.decode_key_len(key, hasher);
+				let (key, value) = self.decode_map_key(&mut state, hasher, key_type)?;
 				StorageKey {
 					module: info.module.name().into(),
 					prefix: info.meta.prefix().to_string(),
-					extra: Some(StorageKeyData::Map {
-						key: key.to_vec(),
-						hasher: hasher.clone(),
-						key_type: key_type.clone(),
-					}),
+					extra: Some(StorageKeyData::Map { key, hasher: hasher.clone(), key_type: key_type.clone(), value }),
 				}
 			}
 			StorageType::DoubleMap { hasher, key1, key2, key2_hasher, .. } => {
-				let key1_bytes = self.decode_key_len(key, hasher);
-				let key2_bytes = self.decode_key_len(&key[key1_bytes.len()..], key2_hasher);
+				let (key1_bytes, key1_value) = self.decode_map_key(&mut state, hasher, key1)?;
+				// The second key may be missing when iterating over all entries under the first.
+				let (key2_bytes, key2_value) = if state.cursor() < key.len() {
+					self.decode_map_key(&mut state, key2_hasher, key2)?
+				} else {
+					(Vec::new(), None)
+				};
 				StorageKey {
 					module: info.module.name().into(),
 					prefix: info.meta.prefix().to_string(),
@@ -393,11 +428,29 @@ impl Decoder {
 						key2: key2_bytes,
 						key1_type: key1.clone(),
 						key2_type: key2.clone(),
+						key1_value,
+						key2_value,
 					}),
 				}
 			}
-			StorageType::NMap { .. } => unimplemented!(),
-		}
+			StorageType::NMap { keys: key_types, hashers, .. } => {
+				// Partial keys are supported; we stop once the key bytes have been exhausted.
+				let mut keys = Vec::new();
+				for (hasher, key_type) in hashers.iter().zip(key_types) {
+					if state.cursor() >= key.len() {
+						break;
+					}
+					let (bytes, value) = self.decode_map_key(&mut state, hasher, key_type)?;
+					keys.push((hasher.clone(), bytes, key_type.clone(), value));
+				}
+				StorageKey {
+					module: info.module.name().into(),
+					prefix: info.meta.prefix().to_string(),
+					extra: Some(StorageKeyData::NMap { keys }),
+				}
+			}
+		};
+		Ok(storage_key)
 	}
 
 	/// Decode the Key/Value pair of a storage entry
@@ -407,56 +460,35 @@ impl Decoder {
 		data: (V, Option<O>),
 	) -> Result<GenericStorage, Error> {
 		let (key, value): (&[u8], Option<O>) = (data.0.as_ref(), data.1);
-		let meta = self.versions.get(&spec).expect("Spec does not exist");
+		let meta = self.versions.get(&spec).ok_or(Error::MissingSpec(spec))?;
 		let lookup_table = meta.storage_lookup_table();
-		let storage_info = lookup_table.meta_for_key(key).ok_or_else(|| {
-			Error::from(format!("Storage not found key={:#X?}, spec={}, chain={}", key, spec, self.chain.as_str()))
-		})?;
-
-		if value.is_none() {
-			let key = self.get_key_data(key, storage_info, &lookup_table);
-			return Ok(GenericStorage::new(key, None));
-		}
-		let value = value.unwrap();
+		let storage_info =
+			lookup_table.meta_for_key(key).ok_or_else(|| Error::StoragePrefixNotFound(hex::encode(key)))?;
+
+		let value = match value {
+			Some(value) => value,
+			None => {
+				let key = self.get_key_data(key, storage_info, &lookup_table, meta, spec)?;
+				return Ok(GenericStorage::new(key, None));
+			}
+		};
 		let value = value.as_ref();
 
-		match &storage_info.meta.ty {
-			StorageType::Plain(rtype) => {
-				log::trace!("{:?}, module {}, spec {}", rtype, storage_info.module.name(), spec);
-				let mut state = DecodeState::new(Some(&storage_info.module), None, meta, 0, spec, value);
-				let value = self.decode_single(&mut state, rtype, false)?;
-				let key = self.get_key_data(key, storage_info, &lookup_table);
-				let storage = GenericStorage::new(key, Some(StorageValue::new(value)));
-				Ok(storage)
-			}
-			StorageType::Map { value: val_rtype, unused: _unused, .. } => {
-				log::trace!(
-					"Resolving storage `Map`. Value: {:?}, module {}, spec {}",
-					val_rtype,
-					storage_info.module.name(),
-					spec
-				);
-				let key = self.get_key_data(key, storage_info, &lookup_table);
-				let mut state = DecodeState::new(Some(&storage_info.module), None, meta, 0, spec, value);
-				let value = self.decode_single(&mut state, val_rtype, false)?;
-				let storage = GenericStorage::new(key, Some(StorageValue::new(value)));
-				Ok(storage)
-			}
-			StorageType::DoubleMap { value: val_rtype, .. } => {
-				log::trace!(
-					"Resolving storage `DoubleMap`. Value: {:?}, module {}, spec {}",
-					value,
-					storage_info.module.name(),
-					spec
-				);
-				let key = self.get_key_data(key, storage_info, &lookup_table);
-				let mut state = DecodeState::new(Some(&storage_info.module), None, meta, 0, spec, value);
-				let value = self.decode_single(&mut state, val_rtype, false)?;
-				let storage = GenericStorage::new(key, Some(StorageValue::new(value)));
-				Ok(storage)
-			}
-			StorageType::NMap { .. } => unimplemented!(),
+		let val_rtype = match &storage_info.meta.ty {
+			StorageType::Plain(rtype) => rtype,
+			StorageType::Map { value, .. } => value,
+			StorageType::DoubleMap { value, .. } => value,
+			StorageType::NMap { value, .. } => value,
+		};
+		log::trace!("Resolving storage value {:?}, module {}, spec {}", val_rtype, storage_info.module.name(), spec);
+		let key = self.get_key_data(key, storage_info, &lookup_table, meta, spec)?;
+		let mut state = DecodeState::new(Some(&storage_info.module), None, meta, 0, spec, value);
+		let decoded = self.decode_single(&mut state, val_rtype, false)?;
+		let remaining = value.len().saturating_sub(state.cursor());
+		if remaining > 0 {
+			return Err(Error::ExcessValueBytes(remaining));
 		}
+		Ok(GenericStorage::new(key, Some(StorageValue::new(decoded))))
 	}
 
 	/// Decode a Vec<Extrinsic>. (Vec<Vec<u8>>)
@@ -470,21 +502,61 @@ impl Decoder {
 		for (idx, extrinsic) in ChunkedExtrinsic::new(&data[prefix..]).enumerate() {
 			log::trace!("Extrinsic {}:{:?}", idx, extrinsic);
 			state.reset(extrinsic);
-			ext.push(self.decode_extrinsic(&mut state)?);
+			ext.push(self.decode_extrinsic(&mut state, idx)?);
 		}
 
 		Ok(ext)
 	}
 
+	/// Decode a Vec<EventRecord>, as found in the `System.Events` storage entry.
+	pub fn decode_events(&self, spec: SpecVersion, data: &[u8]) -> Result<Vec<GenericEvent>, Error> {
+		let mut events = Vec::new();
+		let (length, prefix) = Self::scale_length(data)?;
+		let meta = self.versions.get(&spec).ok_or(Error::MissingSpec(spec))?;
+		log::trace!("Decoding {} Total Events.", length);
+		let mut state = DecodeState::new(None, None, meta, prefix, spec, data);
+		for idx in 0..length {
+			log::trace!("Event {}", idx);
+			events.push(self.decode_event(&mut state)?);
+		}
+
+		Ok(events)
+	}
+
+	/// Decode a single EventRecord; the phase, the event itself and its topics.
+	fn decode_event(&self, state: &mut DecodeState) -> Result<GenericEvent, Error> {
+		let phase: Phase = state.decode()?;
+
+		let module_index: u8 = state.decode()?;
+		let module = state
+			.metadata
+			.module_by_index(ModuleIndex::Event(module_index))
+			.map_err(|e| Error::DetailedMetaFail(e, state.cursor(), hex::encode(state.data)))?;
+		state.module.set(module);
+
+		let event_index: u8 = state.decode()?;
+		let event = module.event(event_index)?;
+		log::trace!("Decoding event {}.{}", module.name(), event.name);
+
+		let mut args = Vec::new();
+		for arg in event.arguments.iter() {
+			let ty = arg.ty()?;
+			args.push(self.decode_single(state, &ty, false)?);
+		}
+		let topics: Vec<sp_core::H256> = state.decode()?;
+
+		Ok(GenericEvent::new(phase, module.name().into(), event.name.clone(), args, topics))
+	}
+
 	/// Decode an extrinsic
-	fn decode_extrinsic(&self, state: &mut DecodeState) -> Result<GenericExtrinsic, Error> {
-		let signature = if state.interpret_version() { Some(self.decode_signature(state)?) } else { None };
+	fn decode_extrinsic(&self, state: &mut DecodeState, index: usize) -> Result<GenericExtrinsic, Error> {
+		let signature = if state.interpret_version()? { Some(self.decode_signature(state)?) } else { None };
 
 		state.load_module()?;
 		let types = self.decode_call(state)?;
 		log::debug!("Finished cursor length={}", state.cursor());
 		let call = state.call.borrow().as_ref().map(|c| c.name()).unwrap_or_else(|| "unknown".into());
-		Ok(GenericExtrinsic::new(signature, types, call, state.module_name().into()))
+		Ok(GenericExtrinsic::new(signature, types, call, state.module_name().into(), index, state.data))
 	}
 
 	/// Decode the signature part of an UncheckedExtrinsic
@@ -565,8 +637,9 @@ impl Decoder {
 				log::trace!("Set::cursor = {}", state.cursor());
 				// a set item must be an u8
 				// can decode this right away
-				let index = state.do_index();
-				SubstrateType::Set(v[index as usize].clone())
+				let index = state.do_index()?;
+				let value = v.get(index as usize).ok_or(Error::InvalidVariant { ty: "Set".into(), index })?;
+				SubstrateType::Set(value.clone())
 			}
 			RustTypeMarker::Tuple(v) => {
 				log::trace!("Tuple::cursor={}", state.cursor());
@@ -579,8 +652,8 @@ impl Decoder {
 			RustTypeMarker::Enum(v) => {
 				log::trace!("Enum::cursor={}", state.cursor());
 				state.observe(line!());
-				let index = state.do_index();
-				let variant = &v[index as usize];
+				let index = state.do_index()?;
+				let variant = v.get(index as usize).ok_or(Error::InvalidVariant { ty: "Enum".into(), index })?;
 				let value = variant.value.as_ref().map(|v| self.decode_single(state, v, is_compact)).transpose()?;
 				log::trace!("Enum: {:?}", value);
 				SubstrateType::Enum(substrate_types::EnumField {
@@ -621,7 +694,7 @@ impl Decoder {
 				}
 				CommonTypes::Option(v) => {
 					log::trace!("Option::cursor={}", state.cursor());
-					match state.do_index() {
+					match state.do_index()? {
 						// None
 						0x00 => SubstrateType::Option(Box::new(None)),
 						// Some
@@ -629,14 +702,12 @@ impl Decoder {
 							let ty = self.decode_single(state, v, is_compact)?;
 							SubstrateType::Option(Box::new(Some(ty)))
 						}
-						_ => {
-							panic!("Cannot deduce correct Option<T> enum variant");
-						}
+						index => return Err(Error::InvalidVariant { ty: "Option<T>".into(), index }),
 					}
 				}
 				CommonTypes::Result(v, e) => {
 					log::trace!("Result::cursor={}", state.cursor());
-					match state.do_index() {
+					match state.do_index()? {
 						// Ok
 						0x00 => {
 							let ty = self.decode_single(state, v, is_compact)?;
@@ -647,9 +718,7 @@ impl Decoder {
 							let ty = self.decode_single(state, e, is_compact)?;
 							SubstrateType::Result(Box::new(Err(ty)))
 						}
-						_ => {
-							panic!("Cannot deduce correct Result<T> Enum Variant");
-						}
+						index => return Err(Error::InvalidVariant { ty: "Result<T, E>".into(), index }),
 					}
 				}
 				CommonTypes::Compact(v) => {
@@ -662,9 +731,7 @@ impl Decoder {
 				// disregard 'inner' type of a generic
 				self.decode_single(state, outer, is_compact)?
 			}
-			RustTypeMarker::Number => {
-				panic!("number decoding not possible");
-			}
+			RustTypeMarker::Number => return Err(Error::UnsupportedType("Number".into())),
 			RustTypeMarker::U8 => {
 				let num: u8 = if is_compact {
 					let num: Compact<u8> = state.decode()?;
@@ -724,33 +791,42 @@ impl Decoder {
 			}
 			RustTypeMarker::I8 => {
 				log::trace!("Decoding i8");
-				let num: i8 = if is_compact { unimplemented!() } else { state.decode()? };
+				if is_compact {
+					return Err(Error::UnsupportedType("Compact<i8>".into()));
+				}
+				let num: i8 = state.decode()?;
 				num.into()
 			}
 			RustTypeMarker::I16 => {
 				log::trace!("Decoding i16");
-				let num: i16 = if is_compact { unimplemented!() } else { state.decode()? };
+				if is_compact {
+					return Err(Error::UnsupportedType("Compact<i16>".into()));
+				}
+				let num: i16 = state.decode()?;
 				num.into()
 			}
 			RustTypeMarker::I32 => {
 				log::trace!("Decoding i32");
-				let num: i32 = if is_compact { unimplemented!() } else { state.decode()? };
+				if is_compact {
+					return Err(Error::UnsupportedType("Compact<i32>".into()));
+				}
+				let num: i32 = state.decode()?;
 				num.into()
 			}
 			RustTypeMarker::I64 => {
 				log::trace!("Decoding i64");
-				let num: i64 = if is_compact {
-					// let num: Compact<i64> = Decode::decode(&mut &data[*cursor..*cursor+8])?;
-					// num.into()
-					unimplemented!()
-				} else {
-					state.decode()?
-				};
+				if is_compact {
+					return Err(Error::UnsupportedType("Compact<i64>".into()));
+				}
+				let num: i64 = state.decode()?;
 				num.into()
 			}
 			RustTypeMarker::I128 => {
 				log::trace!("Decoding i128");
-				let num: i128 = if is_compact { unimplemented!() } else { state.decode()? };
+				if is_compact {
+					return Err(Error::UnsupportedType("Compact<i128>".into()));
+				}
+				let num: i128 = state.decode()?;
 				num.into()
 			}
 			RustTypeMarker::Bool => {
@@ -955,7 +1031,7 @@ fn decode_old_address(state: &DecodeState) -> Result<substrate_types::Address, E
 	}
 
 	let inc;
-	let addr = match state.do_index() {
+	let addr = match state.do_index()? {
 		// do_index for byte 0x00-0xff
 		x @ 0x00..=0xef => {
 			inc = 0;
@@ -1046,6 +1122,99 @@ mod tests {
 		assert_eq!(len.0, 2);
 	}
 
+	#[test]
+	fn should_decode_nmap_storage() {
+		let mut decoder = Decoder::new(GenericTypes, Chain::Kusama);
+		let spec = test_suite::mock_runtime(0).spec_version;
+		decoder.register_version(spec, meta_test_suite::test_metadata()).unwrap();
+
+		let mut key = sp_core::twox_128(b"TestStorage4").to_vec();
+		key.extend(sp_core::twox_64(&1234u32.encode()));
+		key.extend(1234u32.encode());
+		key.extend(sp_core::blake2_128(&5u64.encode()));
+
+		let storage = decoder.decode_storage(spec, (&key, Some(42u32.encode()))).unwrap();
+		assert_eq!(storage.value().map(StorageValue::ty), Some(&SubstrateType::U32(42)));
+		let keys = match &storage.key().extra {
+			Some(StorageKeyData::NMap { keys }) => keys,
+			other => panic!("Expected NMap key data, got {:?}", other),
+		};
+		assert_eq!(keys.len(), 2);
+		assert_eq!(keys[0].0, StorageHasher::Twox64Concat);
+		assert_eq!(keys[0].1, key[16..28].to_vec());
+		assert_eq!(keys[0].3, Some(SubstrateType::U32(1234)));
+		assert_eq!(keys[1].0, StorageHasher::Blake2_128);
+		assert_eq!(keys[1].1, key[28..].to_vec());
+		assert_eq!(keys[1].3, None);
+
+		// A partial key only yields the keys that are present:
+		let storage = decoder.decode_storage(spec, (&key[..28], None::<Vec<u8>>)).unwrap();
+		assert!(matches!(&storage.key().extra, Some(StorageKeyData::NMap { keys }) if keys.len() == 1));
+	}
+
+	#[test]
+	fn should_decode_plain_storage_keys() {
+		let mut decoder = Decoder::new(GenericTypes, Chain::Kusama);
+		let spec = test_suite::mock_runtime(0).spec_version;
+		decoder.register_version(spec, meta_test_suite::test_metadata()).unwrap();
+
+		let mut key = sp_core::twox_128(b"TestStorage5").to_vec();
+		key.extend(77u64.encode());
+		key.extend(sp_core::blake2_128(&9u32.encode()));
+		key.extend(9u32.encode());
+
+		let storage = decoder.decode_storage(spec, (&key, None::<Vec<u8>>)).unwrap();
+		match &storage.key().extra {
+			Some(StorageKeyData::DoubleMap { key1, key1_value, key2, key2_value, .. }) => {
+				assert_eq!(key1, &77u64.encode());
+				assert_eq!(key1_value, &Some(SubstrateType::U64(77)));
+				assert_eq!(key2, &key[24..].to_vec());
+				assert_eq!(key2_value, &Some(SubstrateType::U32(9)));
+			}
+			other => panic!("Expected DoubleMap key data, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn should_error_on_bad_storage_input() {
+		let mut decoder = Decoder::new(GenericTypes, Chain::Kusama);
+		let spec = test_suite::mock_runtime(0).spec_version;
+		decoder.register_version(spec, meta_test_suite::test_metadata()).unwrap();
+		let prefix = sp_core::twox_128(b"TestStorage4").to_vec();
+
+		let res = decoder.decode_storage(spec + 1, (&prefix, None::<Vec<u8>>));
+		assert!(matches!(res, Err(Error::MissingSpec(s)) if s == spec + 1));
+
+		let res = decoder.decode_storage(spec, (&prefix[..4], None::<Vec<u8>>));
+		assert!(matches!(res, Err(Error::StoragePrefixNotFound(_))));
+
+		let mut key = prefix.clone();
+		key.extend([1, 2, 3]);
+		let res = decoder.decode_storage(spec, (&key, None::<Vec<u8>>));
+		assert!(matches!(res, Err(Error::StorageKeyTooShort { expected: 24, found: 19 })));
+
+		let res = decoder.decode_storage(spec, (&prefix, Some(vec![1u8])));
+		assert!(matches!(res, Err(Error::Codec(_))));
+
+		let res = decoder.decode_storage(spec, (&prefix, Some([7u32.encode(), vec![0]].concat())));
+		assert!(matches!(res, Err(Error::ExcessValueBytes(1))));
+	}
+
+	#[test]
+	fn should_error_on_unsupported_types() {
+		let decoder = Decoder::new(GenericTypes, Chain::Kusama);
+		let meta = meta_test_suite::test_metadata();
+		let val = Compact(5u32).encode();
+
+		let mut state = DecodeState::new(None, None, &meta, 0, 1031, val.as_slice());
+		let res = decoder.decode_single(&mut state, &RustTypeMarker::I32, true);
+		assert!(matches!(res, Err(Error::UnsupportedType(ty)) if ty == "Compact<i32>"));
+
+		let mut state = DecodeState::new(None, None, &meta, 0, 1031, val.as_slice());
+		let res = decoder.decode_single(&mut state, &RustTypeMarker::Number, false);
+		assert!(matches!(res, Err(Error::UnsupportedType(ty)) if ty == "Number"));
+	}
+
 	macro_rules! decode_test {
 		( $v: expr, $x:expr, $r: expr) => {{
 			let val = $v.encode();
@@ -1281,4 +1450,28 @@ mod tests {
 		assert_eq!(chunked.next(), Some(vec![3, 4, 5].as_slice()));
 		assert_eq!(chunked.next(), Some(vec![6, 7, 8].as_slice()));
 	}
+
+	#[test]
+	fn should_record_extrinsic_hash_and_index() {
+		let mut decoder = Decoder::new(GenericTypes, Chain::Kusama);
+		let spec = test_suite::mock_runtime(0).spec_version;
+		let mut meta = meta_test_suite::test_metadata();
+		meta.modules_by_call_index.insert(0, "TestModule0".to_string());
+		decoder.register_version(spec, meta).unwrap();
+
+		// Two unsigned V4 extrinsics calling `TestModule0.foo_function1(u64)`.
+		let ext0 = [vec![0x04, 0, 2], 5u64.encode()].concat();
+		let ext1 = [vec![0x04, 0, 2], 6u64.encode()].concat();
+		let encoded = vec![ext0.clone(), ext1.clone()].encode();
+
+		let exts = decoder.decode_extrinsics(spec, &encoded).unwrap();
+		assert_eq!(exts.len(), 2);
+		for (i, (ext, bytes)) in exts.iter().zip([ext0, ext1]).enumerate() {
+			assert_eq!(ext.index(), i);
+			assert_eq!(ext.ext_call(), "foo_function1");
+			assert_eq!(ext.bytes(), bytes.as_slice());
+			assert_eq!(ext.encoded_len(), bytes.len() + 1);
+			assert_eq!(ext.hash(), sp_core::H256(sp_core::blake2_256(&bytes.encode())));
+		}
+	}
 }
@@ -0,0 +1,85 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version. //
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Generic Event Type and Functions
+
+use crate::substrate_types::SubstrateType;
+use parity_scale_codec::Decode;
+use serde::Serialize;
+use sp_core::H256;
+use std::fmt;
+
+/// The phase of block execution in which an event was emitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Decode, Serialize)]
+pub enum Phase {
+	/// Applying the extrinsic at the given index
+	ApplyExtrinsic(u32),
+	/// Finalizing the block
+	Finalization,
+	/// Initializing the block
+	Initialization,
+}
+
+/// Generic Event Type
+#[derive(Debug, Serialize)]
+pub struct GenericEvent {
+	phase: Phase,
+	module: String,
+	name: String,
+	args: Vec<SubstrateType>,
+	topics: Vec<H256>,
+}
+
+impl fmt::Display for GenericEvent {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut s = String::from("");
+		s.push_str(&format!("{:?} ", self.phase));
+		s.push_str(&self.module);
+		s.push('.');
+		s.push_str(&self.name);
+		s.push_str(":   ");
+		for arg in self.args.iter() {
+			s.push_str(&format!(" {} ", arg));
+		}
+		write!(f, "{}", s)
+	}
+}
+
+impl GenericEvent {
+	/// create a new generic event type
+	pub fn new(phase: Phase, module: String, name: String, args: Vec<SubstrateType>, topics: Vec<H256>) -> Self {
+		Self { phase, module, name, args, topics }
+	}
+
+	pub fn phase(&self) -> Phase {
+		self.phase
+	}
+
+	pub fn module(&self) -> &str {
+		&self.module
+	}
+
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	pub fn args(&self) -> &[SubstrateType] {
+		&self.args
+	}
+
+	pub fn topics(&self) -> &[H256] {
+		&self.topics
+	}
+}
@@ -16,7 +16,9 @@
 //! Generic Extrinsic Type and Functions
 
 use crate::substrate_types::SubstrateType;
+use parity_scale_codec::{Compact, Encode};
 use serde::Serialize;
+use sp_core::{blake2_256, H256};
 use std::fmt;
 #[derive(Debug, Serialize)]
 pub struct ExtrinsicArgument {
@@ -52,6 +54,10 @@ impl fmt::Display for GenericCall {
 /// Generic Extrinsic Type
 #[derive(Debug, Serialize)]
 pub struct GenericExtrinsic {
+	index: usize,
+	hash: H256,
+	encoded_len: usize,
+	bytes: Vec<u8>,
 	signature: Option<GenericSignature>,
 	call: GenericCall,
 }
@@ -73,12 +79,49 @@ impl fmt::Display for GenericExtrinsic {
 }
 
 impl GenericExtrinsic {
-	/// create a new generic extrinsic type
-	pub fn new(sig: Option<SubstrateType>, call: Vec<(String, SubstrateType)>, name: String, module: String) -> Self {
+	/// create a new generic extrinsic type, given its position in the block and
+	/// the bytes it was decoded from (not including the length prefix)
+	pub fn new(
+		sig: Option<SubstrateType>,
+		call: Vec<(String, SubstrateType)>,
+		name: String,
+		module: String,
+		index: usize,
+		bytes: &[u8],
+	) -> Self {
 		let call =
 			call.into_iter().map(|c| ExtrinsicArgument { name: c.0, arg: c.1 }).collect::<Vec<ExtrinsicArgument>>();
 		let call = GenericCall { name, module, args: call };
-		Self { signature: sig.map(GenericSignature::new), call }
+		let mut encoded = Compact(bytes.len() as u32).encode();
+		encoded.extend_from_slice(bytes);
+		Self {
+			index,
+			hash: H256(blake2_256(&encoded)),
+			encoded_len: encoded.len(),
+			bytes: bytes.to_vec(),
+			signature: sig.map(GenericSignature::new),
+			call,
+		}
+	}
+
+	/// position of the extrinsic in the block
+	pub fn index(&self) -> usize {
+		self.index
+	}
+
+	/// blake2-256 hash of the SCALE encoded extrinsic (including its length prefix)
+	pub fn hash(&self) -> H256 {
+		self.hash
+	}
+
+	/// length of the SCALE encoded extrinsic (including its length prefix)
+	pub fn encoded_len(&self) -> usize {
+		self.encoded_len
+	}
+
+	/// raw bytes of the extrinsic (not including its length prefix)
+	pub fn bytes(&self) -> &[u8] {
+		&self.bytes
 	}
 
 	pub fn is_signed(&self) -> bool {
@@ -157,6 +200,10 @@ mod tests {
 			args: vec![ExtrinsicArgument { name: "Some Arg".to_string(), arg: SubstrateType::U32(32) }],
 		};
 		let ext = GenericExtrinsic {
+			index: 0,
+			hash: H256::zero(),
+			encoded_len: 1,
+			bytes: Vec::new(),
 			signature: Some(GenericSignature::new(SubstrateType::Composite(vec![
 				SubstrateType::Composite(vec![
 					0u8.into(),
@@ -200,7 +247,7 @@ mod tests {
 		let serialized = serde_json::to_string(&ext).unwrap();
 		assert_eq!(
 			serialized,
-			r#"{"signature":{"address":"5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM","signature":64,"extra":128},"call":{"name":"set","module":"Timestamp","args":[{"name":"Some Arg","arg":32}]}}"#
+			r#"{"index":0,"hash":"0x0000000000000000000000000000000000000000000000000000000000000000","encoded_len":1,"bytes":[],"signature":{"address":"5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM","signature":64,"extra":128},"call":{"name":"set","module":"Timestamp","args":[{"name":"Some Arg","arg":32}]}}"#
 		);
 	}
 }
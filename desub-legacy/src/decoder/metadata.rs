@@ -26,6 +26,7 @@
 //! Must be updated whenever the metadata version is updated
 //! by adding a 'version_xx' file
 
+mod shape;
 #[cfg(test)]
 pub mod test_suite;
 mod version_08;
@@ -38,13 +39,13 @@ mod version_13;
 pub use frame_metadata::{decode_different::DecodeDifferent, RuntimeMetadata, RuntimeMetadataPrefixed};
 
 use super::storage::{StorageInfo, StorageLookupTable};
-use crate::RustTypeMarker;
+use crate::{CommonTypes, RustTypeMarker};
 use parity_scale_codec::{Decode, Encode, EncodeAsRef, HasCompact};
 use serde::{Deserialize, Serialize};
 use sp_core::{storage::StorageKey, twox_128};
 
 use std::{
-	collections::{HashMap, HashSet},
+	collections::HashMap,
 	convert::{TryFrom, TryInto},
 	fmt,
 	marker::PhantomData,
@@ -92,6 +93,8 @@ pub enum Error {
 	InvalidEventArg(String, &'static str),
 	#[error("Invalid Type {0}")]
 	InvalidType(String),
+	#[error("Error {1} not found in module {0}")]
+	ErrorNotFound(String, u8),
 }
 
 #[derive(Debug, Clone, derive_more::Display)]
@@ -99,6 +102,7 @@ pub enum ModuleIndex {
 	Call(u8),
 	Storage(u8),
 	Event(u8),
+	Error(u8),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -208,6 +212,9 @@ impl<'a> Metadata {
 					self.modules_by_event_index.get(&i).ok_or(Error::ModuleIndexNotFound(ModuleIndex::Event(i)))?;
 				self.modules.get(name).ok_or_else(|| Error::ModuleNotFound(name.to_string()))?
 			}
+			ModuleIndex::Error(i) => {
+				self.modules().find(|m| m.index == i).ok_or(Error::ModuleIndexNotFound(ModuleIndex::Error(i)))?
+			}
 			ModuleIndex::Storage(_) => {
 				// TODO remove panics
 				panic!("No storage index stored")
@@ -215,6 +222,17 @@ impl<'a> Metadata {
 		})
 	}
 
+	/// Look up the module and error metadata referred to by a `DispatchError::Module`,
+	/// given the index of the module and the index of the error within it.
+	pub fn module_error(
+		&'a self,
+		module_index: u8,
+		error_index: u8,
+	) -> Result<(&'a ModuleMetadata, &'a ModuleErrorMetadata), Error> {
+		let module = self.module_by_index(ModuleIndex::Error(module_index))?;
+		Ok((module, module.error(error_index)?))
+	}
+
 	/// Returns a hashmap of a Hash -> StorageMetadata
 	/// Hash is prefix of storage entries in metadata
 	pub fn storage_lookup_table(&self) -> StorageLookupTable {
@@ -309,6 +327,8 @@ pub struct ModuleMetadata {
 	/// Calls in the module, CallName -> encoded calls
 	calls: HashMap<String, CallMetadata>,
 	events: HashMap<u8, ModuleEventMetadata>,
+	/// Errors in the module, ordered by their index
+	errors: Vec<ModuleErrorMetadata>,
 	// constants
 }
 
@@ -346,6 +366,16 @@ impl ModuleMetadata {
 	pub fn call(&self, index: u8) -> Result<&CallMetadata, Error> {
 		self.calls().find(|c| c.index == index).ok_or(Error::ModuleIndexNotFound(ModuleIndex::Call(index)))
 	}
+
+	/// iterator over all possible errors for this module, in index order
+	pub fn errors(&self) -> impl Iterator<Item = &ModuleErrorMetadata> {
+		self.errors.iter()
+	}
+
+	/// get an error by its index in the module
+	pub fn error(&self, index: u8) -> Result<&ModuleErrorMetadata, Error> {
+		self.errors.get(index as usize).ok_or_else(|| Error::ErrorNotFound(self.name.clone(), index))
+	}
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -482,15 +512,25 @@ impl<K: Encode, V: Decode + Clone> StorageMap<K, V> {
 #[derive(Clone, Debug, PartialEq)]
 pub struct ModuleEventMetadata {
 	pub name: String,
-	pub(crate) arguments: HashSet<EventArg>,
+	pub(crate) arguments: Vec<EventArg>,
 }
 
 impl ModuleEventMetadata {
+	/// the arguments of this event, in the order they are encoded
 	pub fn arguments(&self) -> Vec<EventArg> {
-		self.arguments.iter().cloned().collect()
+		self.arguments.clone()
 	}
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// Metadata for an error a module may return in a `DispatchError::Module`
+pub struct ModuleErrorMetadata {
+	/// name of the error variant
+	pub name: String,
+	/// documentation attached to the error
+	pub documentation: Vec<String>,
+}
+
 /// Naive representation of event argument types, supports current set of
 /// substrate EventArg types. If and when Substrate uses `type-metadata`, this
 /// can be replaced.
@@ -533,6 +573,17 @@ impl FromStr for EventArg {
 }
 
 impl EventArg {
+	/// Returns the type marker which this EventArg should be decoded as
+	pub fn ty(&self) -> Result<RustTypeMarker, Error> {
+		match self {
+			EventArg::Primitive(p) => crate::regex::parse(p).ok_or_else(|| Error::InvalidType(p.clone())),
+			EventArg::Vec(arg) => Ok(RustTypeMarker::Std(CommonTypes::Vec(Box::new(arg.ty()?)))),
+			EventArg::Tuple(args) => {
+				Ok(RustTypeMarker::Tuple(args.iter().map(EventArg::ty).collect::<Result<_, _>>()?))
+			}
+		}
+	}
+
 	/// Returns all primitive types for this EventArg
 	pub fn primitives(&self) -> Vec<String> {
 		match self {
@@ -568,4 +619,42 @@ pub mod tests {
 		key.extend(twox_128("Account".as_bytes()).to_vec());
 		assert_eq!(first_key, key);
 	}
+	#[test]
+	fn should_find_module_errors_by_index() {
+		let meta = test_suite::test_metadata();
+
+		let (module, error) = meta.module_error(1, 1).unwrap();
+		assert_eq!(module.name(), "TestModule1");
+		assert_eq!(error.name, "TestError1");
+		assert_eq!(error.documentation, vec![" The second test error".to_string()]);
+
+		assert!(matches!(meta.module_error(3, 0), Err(Error::ModuleIndexNotFound(ModuleIndex::Error(3)))));
+		assert!(matches!(meta.module_error(1, 2), Err(Error::ErrorNotFound(m, 2)) if m == "TestModule1"));
+	}
+	#[test]
+	fn should_diff_module_changes() {
+		let old = test_suite::test_metadata();
+		let mut new = old.clone();
+
+		let mut renamed = (*new.modules.remove("TestModule2").unwrap()).clone();
+		renamed.name = "RenamedModule".to_string();
+		new.modules.insert(renamed.name.clone(), Arc::new(renamed));
+		let module = Arc::make_mut(new.modules.get_mut("TestModule0").unwrap());
+		module.errors.push(ModuleErrorMetadata { name: "TestError2".to_string(), documentation: Vec::new() });
+		module.calls.get_mut("TestCall1").unwrap().index = 7;
+
+		let diff = old.diff(&new);
+		assert!(diff.pallets_added.is_empty() && diff.pallets_removed.is_empty());
+		assert_eq!(diff.pallets_renamed.len(), 1);
+		assert_eq!(diff.pallets_renamed[0].from, "TestModule2");
+		assert_eq!(diff.pallets_renamed[0].to, "RenamedModule");
+
+		assert_eq!(diff.pallets_changed.len(), 1);
+		let changed = &diff.pallets_changed[0];
+		assert_eq!(changed.name, "TestModule0");
+		assert_eq!(changed.errors.added, vec!["TestError2".to_string()]);
+		assert_eq!(changed.calls.index_shifts.len(), 1);
+		assert_eq!(changed.calls.index_shifts[0].name, "foo_function1");
+		assert_eq!(changed.calls.index_shifts[0].to, 7);
+	}
 }
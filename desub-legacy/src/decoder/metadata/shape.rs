@@ -0,0 +1,115 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of substrate-desub.
+//
+// substrate-desub is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// substrate-desub is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{EventArg, Metadata, StorageHasher, StorageType};
+use crate::{regex, RustTypeMarker};
+use desub_common::diff::{self, MetadataDiff, MetadataShape, PalletShape, StorageShape, TypeShape, VariantShape};
+
+impl Metadata {
+	/// Describe the parts of this metadata which affect decoding, in a form that can be compared against
+	/// other versions of metadata (including V14+ metadata). Legacy metadata only names the types that
+	/// it refers to, and so types are compared by name against other legacy metadata (with trait projections
+	/// like `<T as Trait>::Call` written as `T::Call`), and are reported as not comparable against V14+ metadata.
+	pub fn shape(&self) -> MetadataShape {
+		let mut modules = self.modules().collect::<Vec<_>>();
+		modules.sort_by_key(|m| m.index);
+
+		let pallets = modules
+			.into_iter()
+			.map(|module| {
+				let mut calls = module
+					.calls()
+					.map(|call| VariantShape {
+						name: call.name.clone(),
+						index: call.index,
+						fields: call
+							.arguments()
+							.map(|arg| type_shape(Some(arg.name.clone()), arg.ty.to_string()))
+							.collect(),
+					})
+					.collect::<Vec<_>>();
+				calls.sort_by_key(|c| c.index);
+
+				let mut events = module
+					.events
+					.iter()
+					.map(|(index, event)| VariantShape {
+						name: event.name.clone(),
+						index: *index,
+						fields: event.arguments.iter().map(|arg| type_shape(None, event_arg_name(arg))).collect(),
+					})
+					.collect::<Vec<_>>();
+				events.sort_by_key(|e| e.index);
+
+				let errors = module
+					.errors()
+					.enumerate()
+					.map(|(index, error)| VariantShape {
+						name: error.name.clone(),
+						index: index as u8,
+						fields: Vec::new(),
+					})
+					.collect();
+
+				let mut storage = module
+					.storage_keys()
+					.map(|(name, entry)| {
+						let (hashers, keys, value) = storage_parts(&entry.ty);
+						StorageShape {
+							name: name.clone(),
+							hashers: hashers.into_iter().map(|h| format!("{h:?}")).collect(),
+							keys: keys.into_iter().map(|k| type_shape(None, k.to_string())).collect(),
+							value: type_shape(None, value.to_string()),
+						}
+					})
+					.collect::<Vec<_>>();
+				storage.sort_by(|a, b| a.name.cmp(&b.name));
+
+				PalletShape { name: module.name.clone(), index: module.index, calls, events, errors, storage }
+			})
+			.collect();
+		MetadataShape { pallets }
+	}
+
+	/// Compare this metadata with some newer version of it. See [`desub_common::diff`] for more.
+	pub fn diff(&self, newer: &Metadata) -> MetadataDiff {
+		diff::diff(&self.shape(), &newer.shape())
+	}
+}
+
+// Legacy types are compared by name, so write trait projections the same way in every version.
+fn type_shape(name: Option<String>, ty: String) -> TypeShape {
+	TypeShape { name, ty: regex::normalize_trait_projections(ty), structure: None }
+}
+
+fn event_arg_name(arg: &EventArg) -> String {
+	match arg {
+		EventArg::Primitive(p) => p.clone(),
+		EventArg::Vec(arg) => format!("Vec<{}>", event_arg_name(arg)),
+		EventArg::Tuple(args) => format!("({})", args.iter().map(event_arg_name).collect::<Vec<_>>().join(", ")),
+	}
+}
+
+fn storage_parts(ty: &StorageType) -> (Vec<&StorageHasher>, Vec<&RustTypeMarker>, &RustTypeMarker) {
+	match ty {
+		StorageType::Plain(value) => (Vec::new(), Vec::new(), value),
+		StorageType::Map { hasher, key, value, .. } => (vec![hasher], vec![key], value),
+		StorageType::DoubleMap { hasher, key1, key2, value, key2_hasher } => {
+			(vec![hasher, key2_hasher], vec![key1, key2], value)
+		}
+		StorageType::NMap { keys, hashers, value } => (hashers.iter().collect(), keys.iter().collect(), value),
+	}
+}
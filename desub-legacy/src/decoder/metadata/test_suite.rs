@@ -40,6 +40,7 @@ fn module_metadata_mock() -> HashMap<String, Arc<ModuleMetadata>> {
 			storage: storage_mock(),
 			calls: call_mock(),
 			events: event_mock(),
+			errors: error_mock(),
 		}),
 	);
 
@@ -51,6 +52,7 @@ fn module_metadata_mock() -> HashMap<String, Arc<ModuleMetadata>> {
 			storage: storage_mock(),
 			calls: call_mock(),
 			events: event_mock(),
+			errors: error_mock(),
 		}),
 	);
 
@@ -62,6 +64,7 @@ fn module_metadata_mock() -> HashMap<String, Arc<ModuleMetadata>> {
 			storage: storage_mock(),
 			calls: call_mock(),
 			events: event_mock(),
+			errors: error_mock(),
 		}),
 	);
 
@@ -117,6 +120,38 @@ fn storage_mock() -> HashMap<String, StorageMetadata> {
 			documentation: vec!["Some Kind of docs 3".to_string()],
 		},
 	);
+
+	map.insert(
+		"TestStorage4".to_string(),
+		StorageMetadata {
+			prefix: "TestStorage4".to_string(),
+			modifier: StorageEntryModifier::Optional,
+			ty: StorageType::NMap {
+				keys: vec![RustTypeMarker::U32, RustTypeMarker::U64],
+				hashers: vec![StorageHasher::Twox64Concat, StorageHasher::Blake2_128],
+				value: RustTypeMarker::U32,
+			},
+			default: vec![0, 0, 0, 0],
+			documentation: vec!["Some Kind of docs 4".to_string()],
+		},
+	);
+
+	map.insert(
+		"TestStorage5".to_string(),
+		StorageMetadata {
+			prefix: "TestStorage5".to_string(),
+			modifier: StorageEntryModifier::Optional,
+			ty: StorageType::DoubleMap {
+				hasher: StorageHasher::Identity,
+				key1: RustTypeMarker::U64,
+				key2: RustTypeMarker::U32,
+				value: RustTypeMarker::U32,
+				key2_hasher: StorageHasher::Blake2_128Concat,
+			},
+			default: vec![0, 0, 0, 0],
+			documentation: vec!["Some Kind of docs 5".to_string()],
+		},
+	);
 	map
 }
 
@@ -160,12 +195,22 @@ fn event_mock() -> HashMap<u8, ModuleEventMetadata> {
 	let event_arg_1 = EventArg::Primitive("TestEvent1".to_string());
 	let event_arg_2 = EventArg::Primitive("TestEvent2".to_string());
 
-	let mut arguments = HashSet::new();
-	arguments.insert(event_arg_0);
-	arguments.insert(event_arg_1);
-	arguments.insert(event_arg_2);
+	let arguments = vec![event_arg_0, event_arg_1, event_arg_2];
 	let module_event_metadata = ModuleEventMetadata { name: "TestEvent0".to_string(), arguments };
 
 	map.insert(0, module_event_metadata);
 	map
 }
+
+fn error_mock() -> Vec<ModuleErrorMetadata> {
+	vec![
+		ModuleErrorMetadata {
+			name: "TestError0".to_string(),
+			documentation: vec![" The first test error".to_string()],
+		},
+		ModuleErrorMetadata {
+			name: "TestError1".to_string(),
+			documentation: vec![" The second test error".to_string()],
+		},
+	]
+}
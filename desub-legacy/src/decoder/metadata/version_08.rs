@@ -18,14 +18,14 @@
 // https://github.com/paritytech/substrate-subxt
 
 use super::{
-	convert, CallArgMetadata, CallMetadata, Error, EventArg, Metadata, ModuleEventMetadata, ModuleMetadata,
-	StorageEntryModifier as DesubStorageEntryModifier, StorageHasher as DesubStorageHasher, StorageMetadata,
-	StorageType,
+	convert, CallArgMetadata, CallMetadata, Error, EventArg, Metadata, ModuleErrorMetadata, ModuleEventMetadata,
+	ModuleMetadata, StorageEntryModifier as DesubStorageEntryModifier, StorageHasher as DesubStorageHasher,
+	StorageMetadata, StorageType,
 };
 use crate::regex;
 use frame_metadata::v8::{self, RuntimeMetadataV8, StorageEntryModifier, StorageEntryType, StorageHasher};
 use std::{
-	collections::{HashMap, HashSet},
+	collections::HashMap,
 	convert::{TryFrom, TryInto},
 };
 
@@ -42,7 +42,7 @@ impl TryFrom<RuntimeMetadataV8> for Metadata {
 				modules_by_call_index.insert(call_index, module_name.clone());
 				call_index += 1;
 			}
-			if module.event.is_none() {
+			if module.event.is_some() {
 				modules_by_event_index.insert(event_index, module_name.clone());
 				event_index += 1;
 			}
@@ -90,25 +90,34 @@ fn convert_module(index: usize, module: v8::ModuleMetadata) -> Result<ModuleMeta
 		}
 	}
 
+	let errors = convert(module.errors)?.into_iter().map(convert_error).collect::<Result<Vec<_>, Error>>()?;
+
 	Ok(ModuleMetadata {
 		index: index as u8,
 		name: convert(module.name)?,
 		storage: storage_map,
 		calls: call_map,
 		events: event_map,
+		errors,
 	})
 }
 
 fn convert_event(event: v8::EventMetadata) -> Result<ModuleEventMetadata, Error> {
 	let name = convert(event.name)?;
-	let mut arguments = HashSet::new();
+	let mut arguments = Vec::new();
 	for arg in convert(event.arguments)? {
 		let arg = arg.parse::<EventArg>()?;
-		arguments.insert(arg);
+		arguments.push(arg);
 	}
 	Ok(ModuleEventMetadata { name, arguments })
 }
 
+fn convert_error(error: v8::ErrorMetadata) -> Result<ModuleErrorMetadata, Error> {
+	let name = convert(error.name)?;
+	let documentation = convert(error.documentation)?;
+	Ok(ModuleErrorMetadata { name, documentation })
+}
+
 fn convert_entry(prefix: String, entry: v8::StorageEntryMetadata) -> Result<StorageMetadata, Error> {
 	let default = convert(entry.default)?;
 	let documentation = convert(entry.documentation)?;
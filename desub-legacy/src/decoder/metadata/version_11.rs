@@ -30,14 +30,14 @@
 // along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::{
-	convert, CallArgMetadata, CallMetadata, Error, EventArg, ExtrinsicMetadata, Metadata, ModuleEventMetadata,
-	ModuleMetadata, StorageEntryModifier as DesubStorageEntryModifier, StorageHasher as DesubStorageHasher,
-	StorageMetadata, StorageType,
+	convert, CallArgMetadata, CallMetadata, Error, EventArg, ExtrinsicMetadata, Metadata, ModuleErrorMetadata,
+	ModuleEventMetadata, ModuleMetadata, StorageEntryModifier as DesubStorageEntryModifier,
+	StorageHasher as DesubStorageHasher, StorageMetadata, StorageType,
 };
 use crate::{regex, RustTypeMarker};
 use frame_metadata::v11::{self, RuntimeMetadataV11, StorageEntryModifier, StorageEntryType, StorageHasher};
 use std::{
-	collections::{HashMap, HashSet},
+	collections::HashMap,
 	convert::{TryFrom, TryInto},
 };
 
@@ -54,7 +54,7 @@ impl TryFrom<RuntimeMetadataV11> for Metadata {
 				modules_by_call_index.insert(call_index, module_name.clone());
 				call_index += 1;
 			}
-			if module.event.is_none() {
+			if module.event.is_some() {
 				modules_by_event_index.insert(event_index, module_name.clone());
 				event_index += 1;
 			}
@@ -111,25 +111,34 @@ fn convert_module(index: usize, module: v11::ModuleMetadata) -> Result<ModuleMet
 		}
 	}
 
+	let errors = convert(module.errors)?.into_iter().map(convert_error).collect::<Result<Vec<_>, Error>>()?;
+
 	Ok(ModuleMetadata {
 		index: index as u8,
 		name: convert(module.name)?,
 		storage: storage_map,
 		calls: call_map,
 		events: event_map,
+		errors,
 	})
 }
 
 fn convert_event(event: v11::EventMetadata) -> Result<ModuleEventMetadata, Error> {
 	let name = convert(event.name)?;
-	let mut arguments = HashSet::new();
+	let mut arguments = Vec::new();
 	for arg in convert(event.arguments)? {
 		let arg = arg.parse::<EventArg>()?;
-		arguments.insert(arg);
+		arguments.push(arg);
 	}
 	Ok(ModuleEventMetadata { name, arguments })
 }
 
+fn convert_error(error: v11::ErrorMetadata) -> Result<ModuleErrorMetadata, Error> {
+	let name = convert(error.name)?;
+	let documentation = convert(error.documentation)?;
+	Ok(ModuleErrorMetadata { name, documentation })
+}
+
 fn convert_entry(prefix: String, entry: v11::StorageEntryMetadata) -> Result<StorageMetadata, Error> {
 	let default = convert(entry.default)?;
 	let documentation = convert(entry.documentation)?;
@@ -163,7 +172,7 @@ impl From<TempStorageHasher> for DesubStorageHasher {
 		let hasher = hasher.0;
 		match hasher {
 			StorageHasher::Blake2_128 => DesubStorageHasher::Blake2_128,
-			StorageHasher::Blake2_128Concat => DesubStorageHasher::Blake2_128,
+			StorageHasher::Blake2_128Concat => DesubStorageHasher::Blake2_128Concat,
 			StorageHasher::Blake2_256 => DesubStorageHasher::Blake2_256,
 			StorageHasher::Twox128 => DesubStorageHasher::Twox128,
 			StorageHasher::Twox256 => DesubStorageHasher::Twox256,
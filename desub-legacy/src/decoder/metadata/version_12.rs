@@ -30,20 +30,20 @@
 // along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::{
-	convert, CallArgMetadata, CallMetadata, Error, EventArg, ExtrinsicMetadata, Metadata, ModuleEventMetadata,
-	ModuleMetadata, StorageEntryModifier as DesubStorageEntryModifier, StorageHasher as DesubStorageHasher,
-	StorageMetadata, StorageType,
+	convert, CallArgMetadata, CallMetadata, Error, EventArg, ExtrinsicMetadata, Metadata, ModuleErrorMetadata,
+	ModuleEventMetadata, ModuleMetadata, StorageEntryModifier as DesubStorageEntryModifier,
+	StorageHasher as DesubStorageHasher, StorageMetadata, StorageType,
 };
 use crate::{regex, RustTypeMarker};
 
 use frame_metadata::v12::{
-	EventMetadata as EventMetadatav12, ModuleMetadata as ModuleMetadatav12, RuntimeMetadataV12,
-	StorageEntryMetadata as StorageEntryMetadatav12, StorageEntryModifier as StorageEntryModifierv12, StorageEntryType,
-	StorageHasher as StorageHasherv12,
+	ErrorMetadata as ErrorMetadatav12, EventMetadata as EventMetadatav12, ModuleMetadata as ModuleMetadatav12,
+	RuntimeMetadataV12, StorageEntryMetadata as StorageEntryMetadatav12,
+	StorageEntryModifier as StorageEntryModifierv12, StorageEntryType, StorageHasher as StorageHasherv12,
 };
 
 use std::{
-	collections::{HashMap, HashSet},
+	collections::HashMap,
 	convert::{TryFrom, TryInto},
 };
 
@@ -52,15 +52,13 @@ impl TryFrom<RuntimeMetadataV12> for Metadata {
 	fn try_from(metadata: RuntimeMetadataV12) -> Result<Self, Self::Error> {
 		let mut modules = HashMap::new();
 		let (mut modules_by_event_index, mut modules_by_call_index) = (HashMap::new(), HashMap::new());
-		let mut event_index = 0;
 		for module in convert(metadata.modules)?.into_iter() {
 			let module_name = convert(module.name.clone())?;
 			if module.calls.is_some() {
 				modules_by_call_index.insert(module.index, module_name.clone());
 			}
-			if module.event.is_none() {
-				modules_by_event_index.insert(event_index, module_name.clone());
-				event_index += 1;
+			if module.event.is_some() {
+				modules_by_event_index.insert(module.index, module_name.clone());
 			}
 			let module_metadata = convert_module(module)?;
 			modules.insert(module_name, std::sync::Arc::new(module_metadata));
@@ -115,25 +113,34 @@ fn convert_module(module: ModuleMetadatav12) -> Result<ModuleMetadata, Error> {
 		}
 	}
 
+	let errors = convert(module.errors)?.into_iter().map(convert_error).collect::<Result<Vec<_>, Error>>()?;
+
 	Ok(ModuleMetadata {
 		index: module.index,
 		name: convert(module.name)?,
 		storage: storage_map,
 		calls: call_map,
 		events: event_map,
+		errors,
 	})
 }
 
 fn convert_event(event: EventMetadatav12) -> Result<ModuleEventMetadata, Error> {
 	let name = convert(event.name)?;
-	let mut arguments = HashSet::new();
+	let mut arguments = Vec::new();
 	for arg in convert(event.arguments)? {
 		let arg = arg.parse::<EventArg>()?;
-		arguments.insert(arg);
+		arguments.push(arg);
 	}
 	Ok(ModuleEventMetadata { name, arguments })
 }
 
+fn convert_error(error: ErrorMetadatav12) -> Result<ModuleErrorMetadata, Error> {
+	let name = convert(error.name)?;
+	let documentation = convert(error.documentation)?;
+	Ok(ModuleErrorMetadata { name, documentation })
+}
+
 fn convert_entry(prefix: String, entry: StorageEntryMetadatav12) -> Result<StorageMetadata, Error> {
 	let default = convert(entry.default)?;
 	let documentation = convert(entry.documentation)?;
@@ -167,7 +174,7 @@ impl From<TempStorageHasher> for DesubStorageHasher {
 		let hasher = hasher.0;
 		match hasher {
 			StorageHasherv12::Blake2_128 => DesubStorageHasher::Blake2_128,
-			StorageHasherv12::Blake2_128Concat => DesubStorageHasher::Blake2_128,
+			StorageHasherv12::Blake2_128Concat => DesubStorageHasher::Blake2_128Concat,
 			StorageHasherv12::Blake2_256 => DesubStorageHasher::Blake2_256,
 			StorageHasherv12::Twox128 => DesubStorageHasher::Twox128,
 			StorageHasherv12::Twox256 => DesubStorageHasher::Twox256,
@@ -30,20 +30,20 @@
 // along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::{
-	convert, CallArgMetadata, CallMetadata, Error, EventArg, ExtrinsicMetadata, Metadata, ModuleEventMetadata,
-	ModuleMetadata, StorageEntryModifier as DesubStorageEntryModifier, StorageHasher as DesubStorageHasher,
-	StorageMetadata, StorageType,
+	convert, CallArgMetadata, CallMetadata, Error, EventArg, ExtrinsicMetadata, Metadata, ModuleErrorMetadata,
+	ModuleEventMetadata, ModuleMetadata, StorageEntryModifier as DesubStorageEntryModifier,
+	StorageHasher as DesubStorageHasher, StorageMetadata, StorageType,
 };
 use crate::{regex, RustTypeMarker};
 
 use frame_metadata::v13::{
-	EventMetadata as EventMetadataV13, ModuleMetadata as ModuleMetadataV13, RuntimeMetadataV13,
-	StorageEntryMetadata as StorageEntryMetadataV13, StorageEntryModifier as StorageEntryModifierV13, StorageEntryType,
-	StorageHasher as StorageHasherV13,
+	ErrorMetadata as ErrorMetadataV13, EventMetadata as EventMetadataV13, ModuleMetadata as ModuleMetadataV13,
+	RuntimeMetadataV13, StorageEntryMetadata as StorageEntryMetadataV13,
+	StorageEntryModifier as StorageEntryModifierV13, StorageEntryType, StorageHasher as StorageHasherV13,
 };
 
 use std::{
-	collections::{HashMap, HashSet},
+	collections::HashMap,
 	convert::{TryFrom, TryInto},
 };
 
@@ -53,15 +53,13 @@ impl TryFrom<RuntimeMetadataV13> for Metadata {
 	fn try_from(metadata: RuntimeMetadataV13) -> Result<Self, Self::Error> {
 		let mut modules = HashMap::new();
 		let (mut modules_by_event_index, mut modules_by_call_index) = (HashMap::new(), HashMap::new());
-		let mut event_index = 0;
 		for module in convert(metadata.modules)?.into_iter() {
 			let module_name = convert(module.name.clone())?;
 			if module.calls.is_some() {
 				modules_by_call_index.insert(module.index, module_name.clone());
 			}
-			if module.event.is_none() {
-				modules_by_event_index.insert(event_index, module_name.clone());
-				event_index += 1;
+			if module.event.is_some() {
+				modules_by_event_index.insert(module.index, module_name.clone());
 			}
 			let module_metadata = convert_module(module)?;
 			modules.insert(module_name, std::sync::Arc::new(module_metadata));
@@ -116,25 +114,34 @@ fn convert_module(module: ModuleMetadataV13) -> Result<ModuleMetadata, Error> {
 		}
 	}
 
+	let errors = convert(module.errors)?.into_iter().map(convert_error).collect::<Result<Vec<_>, Error>>()?;
+
 	Ok(ModuleMetadata {
 		index: module.index,
 		name: convert(module.name)?,
 		storage: storage_map,
 		calls: call_map,
 		events: event_map,
+		errors,
 	})
 }
 
 fn convert_event(event: EventMetadataV13) -> Result<ModuleEventMetadata, Error> {
 	let name = convert(event.name)?;
-	let mut arguments = HashSet::new();
+	let mut arguments = Vec::new();
 	for arg in convert(event.arguments)? {
 		let arg = arg.parse::<EventArg>()?;
-		arguments.insert(arg);
+		arguments.push(arg);
 	}
 	Ok(ModuleEventMetadata { name, arguments })
 }
 
+fn convert_error(error: ErrorMetadataV13) -> Result<ModuleErrorMetadata, Error> {
+	let name = convert(error.name)?;
+	let documentation = convert(error.documentation)?;
+	Ok(ModuleErrorMetadata { name, documentation })
+}
+
 fn convert_entry(prefix: String, entry: StorageEntryMetadataV13) -> Result<StorageMetadata, Error> {
 	let default = convert(entry.default)?;
 	let documentation = convert(entry.documentation)?;
@@ -168,7 +175,7 @@ impl From<TempStorageHasher> for DesubStorageHasher {
 		let hasher = hasher.0;
 		match hasher {
 			StorageHasherV13::Blake2_128 => DesubStorageHasher::Blake2_128,
-			StorageHasherV13::Blake2_128Concat => DesubStorageHasher::Blake2_128,
+			StorageHasherV13::Blake2_128Concat => DesubStorageHasher::Blake2_128Concat,
 			StorageHasherV13::Blake2_256 => DesubStorageHasher::Blake2_256,
 			StorageHasherV13::Twox128 => DesubStorageHasher::Twox128,
 			StorageHasherV13::Twox256 => DesubStorageHasher::Twox256,
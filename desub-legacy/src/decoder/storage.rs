@@ -53,12 +53,12 @@ impl StorageLookupTable {
 	}
 
 	pub fn meta_for_key(&self, key: &[u8]) -> Option<&StorageInfo> {
-		let key = self.table.keys().find(|&k| &key[..k.len()] == k.as_slice());
+		let key = self.table.keys().find(|k| key.starts_with(k));
 		key.and_then(|k| self.lookup(k))
 	}
 
 	pub fn extra_key_data<'a>(&self, key: &'a [u8]) -> Option<&'a [u8]> {
-		let k = self.table.keys().find(|k| &key[..k.len()] == k.as_slice());
+		let k = self.table.keys().find(|k| key.starts_with(k));
 
 		k.map(|k| &key[k.len()..])
 	}
@@ -71,6 +71,8 @@ pub enum StorageKeyData {
 		/// hashed and scale-encoded key
 		key: Vec<u8>,
 		key_type: RustTypeMarker,
+		/// the decoded key, if the hasher keeps it around in plain form
+		value: Option<SubstrateType>,
 	},
 	DoubleMap {
 		hasher: StorageHasher,
@@ -81,6 +83,14 @@ pub enum StorageKeyData {
 		key2: Vec<u8>,
 		key2_type: RustTypeMarker,
 		key2_hasher: StorageHasher,
+		/// the decoded keys, if their hashers keep them around in plain form
+		key1_value: Option<SubstrateType>,
+		key2_value: Option<SubstrateType>,
+	},
+	NMap {
+		/// hasher, hashed and scale-encoded key, key type and decoded key (if the
+		/// hasher keeps it around in plain form) for each key present.
+		keys: Vec<(StorageHasher, Vec<u8>, RustTypeMarker, Option<SubstrateType>)>,
 	},
 }
 
@@ -20,6 +20,19 @@ pub enum Error {
 	Conversion(String, String),
 	#[error("Spec version {0} not present in Decoder")]
 	MissingSpec(u32),
+	#[error("Storage key {0} does not match any storage prefix in the metadata")]
+	StoragePrefixNotFound(String),
+	/// The lengths are those of the whole storage key, including its prefix.
+	#[error("Storage key is too short; expected at least {expected} bytes but got {found}")]
+	StorageKeyTooShort { expected: usize, found: usize },
+	#[error("Failed to decode the storage value: {0} bytes of the input were not consumed")]
+	ExcessValueBytes(usize),
+	#[error("Unexpected end of data at byte {0}")]
+	UnexpectedEnd(usize),
+	#[error("Invalid variant index {index} for {ty}")]
+	InvalidVariant { ty: String, index: u8 },
+	#[error("Decoding values of type {0} is not supported")]
+	UnsupportedType(String),
 }
 
 impl From<&str> for Error {
@@ -175,6 +175,13 @@ pub fn remove_trait<S: AsRef<str>>(s: S) -> Option<String> {
 	caps.iter().nth(1)?.map(|s| s.to_string())
 }
 
+/// Rewrites trait projections as plain associated types, so that `<T as Trait>::Call`,
+/// `<T as Config>::Call` and `<T as frame_system::Config<I>>::Call` all become `T::Call`.
+pub fn normalize_trait_projections<S: AsRef<str>>(s: S) -> String {
+	let re = Regex::new(r"<T as (?:\w+::)*\w+(?:<\w+>)?>::").expect("Regex expression should be infallible; qed");
+	re.replace_all(s.as_ref(), "T::")
+}
+
 pub fn remove_empty_generic<S: AsRef<str>>(s: S) -> Option<String> {
 	let s: &str = s.as_ref();
 
@@ -893,6 +900,18 @@ mod tests {
 		// assert_eq!(remove_prefix("Period<T::BlockNumber>").unwrap(), "BlockNumber");
 	}
 
+	#[test]
+	fn should_normalize_trait_projections() {
+		assert_eq!(normalize_trait_projections("Vec<<T as Trait>::Call>"), "Vec<T::Call>");
+		assert_eq!(normalize_trait_projections("Vec<<T as Config>::Call>"), "Vec<T::Call>");
+		assert_eq!(normalize_trait_projections("<T as frame_system::Config<I>>::AccountId"), "T::AccountId");
+		assert_eq!(normalize_trait_projections("(T::Hash, <T as Trait>::Balance)"), "(T::Hash, T::Balance)");
+		assert_eq!(
+			normalize_trait_projections("<T::Lookup as StaticLookup>::Source"),
+			"<T::Lookup as StaticLookup>::Source"
+		);
+	}
+
 	#[test]
 	fn should_parse_box() {
 		assert_eq!(parse("Box<T::Proposal>").unwrap(), RustTypeMarker::TypePointer("T::Proposal".to_string()))
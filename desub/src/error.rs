@@ -15,7 +15,7 @@
 // along with substrate-desub.  If not, see <http://www.gnu.org/licenses/>.
 
 use desub_current::{
-	decoder::{DecodeError, Extrinsic},
+	decoder::{DecodeError, DecodeValueError, EventRecord, Extrinsic, StorageDecodeError},
 	metadata::MetadataError,
 };
 use desub_legacy::{decoder::metadata::Error as LegacyMetadataError, Error as LegacyError};
@@ -29,6 +29,16 @@ pub enum Error {
 		source: DecodeError,
 		ext: Vec<Extrinsic<'static>>,
 	},
+	#[error("Decoding v14 events failed {source}")]
+	V14Events {
+		#[source]
+		source: DecodeError,
+		events: Vec<EventRecord<'static>>,
+	},
+	#[error(transparent)]
+	V14Storage(#[from] StorageDecodeError),
+	#[error(transparent)]
+	V14Value(#[from] DecodeValueError),
 	#[error(transparent)]
 	Legacy(#[from] LegacyError),
 	#[error(transparent)]
@@ -20,23 +20,26 @@
 mod error;
 
 use desub_current::{
-	decoder::{self, Extrinsic},
+	decoder::{self, EventRecord, Extrinsic, StorageDecoder, StorageHasher},
 	Metadata as DesubMetadata,
 };
 use desub_legacy::{
-	decoder::{Decoder as LegacyDecoder, Metadata as LegacyDesubMetadata},
+	decoder::{Decoder as LegacyDecoder, Metadata as LegacyDesubMetadata, StorageKeyData},
 	RustTypeMarker, TypeDetective,
 };
 use frame_metadata::RuntimeMetadataPrefixed;
 use parity_scale_codec::Decode;
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::collections::HashMap;
 
 #[cfg(feature = "polkadot-js")]
 use desub_json_resolver::TypeResolver as PolkadotJsResolver;
 
 pub use self::error::Error;
-pub use desub_common::SpecVersion;
+pub use desub_common::{
+	diff::{self, MetadataDiff, MetadataShape},
+	SpecVersion,
+};
 #[cfg(feature = "polkadot-js")]
 pub use desub_json_resolver::runtimes;
 pub use desub_legacy::decoder::Chain;
@@ -64,6 +67,8 @@ impl TypeDetective for NoLegacyTypes {
 pub struct Decoder {
 	legacy_decoder: LegacyDecoder,
 	current_metadata: HashMap<SpecVersion, DesubMetadata>,
+	/// Storage decoders are non-trivial to create, so we keep one around for each V14+ spec.
+	current_storage: HashMap<SpecVersion, StorageDecoder>,
 }
 
 impl Decoder {
@@ -71,23 +76,26 @@ impl Decoder {
 	pub fn new(chain: Chain) -> Self {
 		let legacy_decoder = LegacyDecoder::new(PolkadotJsResolver::default(), chain);
 		let current_metadata = HashMap::new();
+		let current_storage = HashMap::new();
 
-		Self { legacy_decoder, current_metadata }
+		Self { legacy_decoder, current_metadata, current_storage }
 	}
 
 	#[cfg(not(feature = "polkadot-js"))]
 	pub fn new() -> Self {
 		let legacy_decoder = LegacyDecoder::new(NoLegacyTypes, Chain::Custom("none".to_string()));
 		let current_metadata = HashMap::new();
+		let current_storage = HashMap::new();
 
-		Self { legacy_decoder, current_metadata }
+		Self { legacy_decoder, current_metadata, current_storage }
 	}
 
 	/// Create a new general Decoder
 	pub fn with_custom_types(types: impl TypeDetective + 'static, chain: Chain) -> Self {
 		let legacy_decoder = LegacyDecoder::new(types, chain);
 		let current_decoder = HashMap::new();
-		Self { legacy_decoder, current_metadata: current_decoder }
+		let current_storage = HashMap::new();
+		Self { legacy_decoder, current_metadata: current_decoder, current_storage }
 	}
 
 	/// Register a runtime version with the decoder.
@@ -95,6 +103,7 @@ impl Decoder {
 		let metadata: RuntimeMetadataPrefixed = Decode::decode(&mut metadata)?;
 		if metadata.1.version() >= 14 {
 			let meta = DesubMetadata::from_runtime_metadata(metadata.1)?;
+			self.current_storage.insert(version, decoder::decode_storage(&meta));
 			self.current_metadata.insert(version, meta);
 		} else {
 			self.legacy_decoder.register_version(version, LegacyDesubMetadata::from_runtime_metadata(metadata.1)?)?;
@@ -120,7 +129,135 @@ impl Decoder {
 		}
 	}
 
+	/// Decode a SCALE encoded vector of event records (ie the contents of `System.Events`). Regardless of
+	/// the metadata version in use, each event is returned in the same shape:
+	///
+	/// `{ "phase": .., "pallet": .., "event": .., "args": [..], "topics": [..] }`
+	pub fn decode_events(&self, version: SpecVersion, mut data: &[u8]) -> Result<Value, Error> {
+		let events = if let Some(metadata) = self.current_metadata.get(&version) {
+			let events = decoder::decode_events(metadata, &mut data).map_err(|(events, e)| Error::V14Events {
+				source: e,
+				events: events.into_iter().map(EventRecord::into_owned).collect(),
+			})?;
+			events
+				.into_iter()
+				.map(|record| {
+					Ok(json!({
+						"phase": record.phase,
+						"pallet": record.event.pallet_name,
+						"event": record.event.ty.name,
+						"args": serde_json::to_value(&record.event.fields)?,
+						"topics": record.topics,
+					}))
+				})
+				.collect::<Result<Vec<_>, Error>>()?
+		} else {
+			if !self.legacy_decoder.has_version(&version) {
+				return Err(Error::SpecVersionNotFound(version));
+			}
+			let events = self.legacy_decoder.decode_events(version, data)?;
+			events
+				.iter()
+				.map(|event| {
+					Ok(json!({
+						"phase": event.phase(),
+						"pallet": event.module(),
+						"event": event.name(),
+						"args": serde_json::to_value(event.args())?,
+						"topics": event.topics(),
+					}))
+				})
+				.collect::<Result<Vec<_>, Error>>()?
+		};
+		Ok(Value::Array(events))
+	}
+
+	/// Decode a storage key, and the value found at it if one is provided. Regardless of the metadata
+	/// version in use, the result is returned in the same shape:
+	///
+	/// `{ "pallet": .., "name": .., "keys": [{ "hasher": .., "bytes": .., "value": .. }], "value": .. }`
+	///
+	/// Where `keys` is empty for plain storage entries, and a key `value` is only present if it can be
+	/// recovered from the hashed key.
+	pub fn decode_storage(&self, version: SpecVersion, key: &[u8], value: Option<&[u8]>) -> Result<Value, Error> {
+		if let (Some(metadata), Some(storage_decoder)) =
+			(self.current_metadata.get(&version), self.current_storage.get(&version))
+		{
+			let entry = storage_decoder.decode_key(metadata, &mut &*key)?;
+			let keys = entry
+				.details
+				.map_keys()
+				.iter()
+				.map(|key| {
+					let (hasher, value) = match &key.hasher {
+						StorageHasher::Blake2_128 => ("Blake2_128", None),
+						StorageHasher::Blake2_256 => ("Blake2_256", None),
+						StorageHasher::Blake2_128Concat(v) => ("Blake2_128Concat", Some(v)),
+						StorageHasher::Twox128 => ("Twox128", None),
+						StorageHasher::Twox256 => ("Twox256", None),
+						StorageHasher::Twox64Concat(v) => ("Twox64Concat", Some(v)),
+						StorageHasher::Identity(v) => ("Identity", Some(v)),
+					};
+					Ok(json!({ "hasher": hasher, "bytes": key.bytes, "value": serde_json::to_value(value)? }))
+				})
+				.collect::<Result<Vec<_>, Error>>()?;
+			// Unlike `decode_value_by_id`, this checks that all of the value bytes were consumed.
+			let value = value
+				.map(|value| storage_decoder.decode_value(metadata, &entry, Some(value)))
+				.transpose()?
+				.flatten()
+				.map(serde_json::to_value)
+				.transpose()?;
+			Ok(json!({ "pallet": entry.prefix, "name": entry.name, "keys": keys, "value": value }))
+		} else {
+			if !self.legacy_decoder.has_version(&version) {
+				return Err(Error::SpecVersionNotFound(version));
+			}
+			let storage = self.legacy_decoder.decode_storage(version, (key, value))?;
+			let storage_key = storage.key();
+			let keys = match &storage_key.extra {
+				None => Vec::new(),
+				Some(StorageKeyData::Map { hasher, key, value, .. }) => {
+					vec![json!({ "hasher": hasher, "bytes": key, "value": value })]
+				}
+				Some(StorageKeyData::DoubleMap { hasher, key1, key2, key2_hasher, key1_value, key2_value, .. }) => {
+					vec![
+						json!({ "hasher": hasher, "bytes": key1, "value": key1_value }),
+						json!({ "hasher": key2_hasher, "bytes": key2, "value": key2_value }),
+					]
+				}
+				Some(StorageKeyData::NMap { keys }) => keys
+					.iter()
+					.map(|(hasher, key, _, value)| json!({ "hasher": hasher, "bytes": key, "value": value }))
+					.collect(),
+			};
+			// Legacy storage prefixes take the form "{prefix} {name}":
+			let (prefix, name) =
+				storage_key.prefix.split_once(' ').unwrap_or((&storage_key.module, &storage_key.prefix));
+			let value = storage.value().map(|v| serde_json::to_value(v.ty())).transpose()?;
+			Ok(json!({ "pallet": prefix, "name": name, "keys": keys, "value": value }))
+		}
+	}
+
 	pub fn has_version(&self, version: SpecVersion) -> bool {
 		self.current_metadata.contains_key(&version) || self.legacy_decoder.has_version(&version)
 	}
+
+	/// Report what changed in the metadata between two registered runtime versions. Either version
+	/// may use legacy or V14+ metadata, but types can only be compared when both use the same kind;
+	/// otherwise anything whose types couldn't be compared is listed as `not_comparable`.
+	/// See [`desub_common::diff`] for more.
+	pub fn diff_versions(&self, from: SpecVersion, to: SpecVersion) -> Result<MetadataDiff, Error> {
+		Ok(diff::diff(&self.metadata_shape(from)?, &self.metadata_shape(to)?))
+	}
+
+	fn metadata_shape(&self, version: SpecVersion) -> Result<MetadataShape, Error> {
+		if let Some(meta) = self.current_metadata.get(&version) {
+			Ok(meta.shape())
+		} else if let Some(meta) = self.legacy_decoder.get_version_metadata(version) {
+			Ok(meta.shape())
+		} else {
+			Err(Error::SpecVersionNotFound(version))
+		}
+	}
 }
@@ -0,0 +1,89 @@
+use crate::runtime_metadata::*;
+use desub_legacy::{
+	decoder::{Chain, Decoder, Metadata, Phase},
+	SubstrateType,
+};
+use parity_scale_codec::{Compact, Encode};
+use sp_core::H256;
+
+fn decoder() -> Decoder {
+	let types = desub_json_resolver::TypeResolver::default();
+	let mut decoder = Decoder::new(types, Chain::Kusama);
+
+	let meta = runtime_v11();
+	let meta = Metadata::new(meta.as_slice()).unwrap();
+	decoder.register_version(2023, meta).unwrap();
+	decoder
+}
+
+/// `Balances.Transfer(from, to, amount)` in meta V11
+fn transfer_event(amount: u128) -> Vec<u8> {
+	let mut bytes = vec![2u8, 2];
+	bytes.extend([1u8; 32]);
+	bytes.extend([2u8; 32]);
+	bytes.extend(amount.encode());
+	bytes
+}
+
+/// `System.ExtrinsicSuccess(DispatchInfo)` in meta V11
+fn extrinsic_success_event(weight: u64) -> Vec<u8> {
+	let mut bytes = vec![0u8, 0];
+	bytes.extend(weight.encode());
+	bytes.extend([0u8, 0]);
+	bytes
+}
+
+#[test]
+fn should_decode_events() {
+	let _ = pretty_env_logger::try_init();
+	let decoder = decoder();
+	let topic = H256::repeat_byte(0xAB);
+
+	let mut bytes = Compact(3u32).encode();
+	// Phase::ApplyExtrinsic(1)
+	bytes.push(0);
+	bytes.extend(1u32.encode());
+	bytes.extend(transfer_event(1000));
+	bytes.extend(Vec::<H256>::new().encode());
+	// Phase::ApplyExtrinsic(1)
+	bytes.push(0);
+	bytes.extend(1u32.encode());
+	bytes.extend(extrinsic_success_event(125_000_000));
+	bytes.extend(vec![topic].encode());
+	// Phase::Finalization
+	bytes.push(1);
+	bytes.extend(extrinsic_success_event(0));
+	bytes.extend(Vec::<H256>::new().encode());
+
+	let events = decoder.decode_events(2023, &bytes).unwrap();
+	assert_eq!(events.len(), 3);
+
+	assert_eq!(events[0].phase(), Phase::ApplyExtrinsic(1));
+	assert_eq!(events[0].module(), "Balances");
+	assert_eq!(events[0].name(), "Transfer");
+	assert_eq!(events[0].args().len(), 3);
+	assert_eq!(events[0].args()[2], SubstrateType::U128(1000));
+	assert!(events[0].topics().is_empty());
+
+	assert_eq!(events[1].phase(), Phase::ApplyExtrinsic(1));
+	assert_eq!(events[1].module(), "System");
+	assert_eq!(events[1].name(), "ExtrinsicSuccess");
+	assert_eq!(events[1].args().len(), 1);
+	assert_eq!(events[1].topics(), &[topic]);
+
+	assert_eq!(events[2].phase(), Phase::Finalization);
+	assert_eq!(events[2].name(), "ExtrinsicSuccess");
+}
+
+#[test]
+fn should_error_on_unknown_event() {
+	let _ = pretty_env_logger::try_init();
+	let decoder = decoder();
+
+	let mut bytes = Compact(1u32).encode();
+	bytes.push(1);
+	bytes.extend([200u8, 0]);
+	bytes.extend(Vec::<H256>::new().encode());
+
+	assert!(decoder.decode_events(2023, &bytes).is_err());
+}
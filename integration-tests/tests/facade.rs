@@ -0,0 +1,151 @@
+use crate::runtime_metadata::*;
+use desub::{Chain, Decoder, Error};
+use parity_scale_codec::{Compact, Encode};
+use serde_json::Value;
+use sp_core::{blake2_128, twox_128, H256};
+
+static V14_METADATA_POLKADOT_SCALE: &[u8] =
+	include_bytes!("../../desub-current/tests/data/v14_metadata_polkadot.scale");
+
+const LEGACY_SPEC: u32 = 2023;
+const CURRENT_SPEC: u32 = 9110;
+const V13_SPEC: u32 = 9080;
+
+fn decoder() -> Decoder {
+	let types = desub_json_resolver::TypeResolver::default();
+	let mut decoder = Decoder::with_custom_types(types, Chain::Kusama);
+	decoder.register_version(LEGACY_SPEC, &runtime_v11()).unwrap();
+	decoder.register_version(CURRENT_SPEC, V14_METADATA_POLKADOT_SCALE).unwrap();
+	decoder
+}
+
+fn keys_of(value: &Value) -> Vec<&str> {
+	value.as_object().expect("an object").keys().map(|k| k.as_str()).collect()
+}
+
+/// A single `Balances.Transfer(from, to, amount)` event record, emitted during the first extrinsic.
+/// The Balances pallet is at index 2 in the V11 metadata, and at index 5 in the V14 metadata.
+fn transfer_event_record(pallet_index: u8) -> Vec<u8> {
+	let mut bytes = Compact(1u32).encode();
+	bytes.push(0);
+	bytes.extend(0u32.encode());
+	bytes.extend([pallet_index, 2]);
+	bytes.extend([1u8; 32]);
+	bytes.extend([2u8; 32]);
+	bytes.extend(1000u128.encode());
+	bytes.extend(vec![H256::repeat_byte(0xAB)].encode());
+	bytes
+}
+
+fn storage_key(pallet: &str, name: &str) -> Vec<u8> {
+	[twox_128(pallet.as_bytes()), twox_128(name.as_bytes())].concat()
+}
+
+#[test]
+fn events_have_the_same_shape_in_each_era() {
+	let decoder = decoder();
+
+	for (spec, pallet_index) in [(LEGACY_SPEC, 2), (CURRENT_SPEC, 5)] {
+		let events = decoder.decode_events(spec, &transfer_event_record(pallet_index)).unwrap();
+		let events = events.as_array().expect("an array of events");
+		assert_eq!(events.len(), 1);
+
+		let event = &events[0];
+		assert_eq!(keys_of(event), ["phase", "pallet", "event", "args", "topics"], "spec {spec}");
+		assert_eq!(event["pallet"], "Balances");
+		assert_eq!(event["event"], "Transfer");
+		assert_eq!(event["args"].as_array().map(Vec::len), Some(3));
+		assert_eq!(event["topics"].as_array().map(Vec::len), Some(1));
+	}
+}
+
+#[test]
+fn storage_has_the_same_shape_in_each_era() {
+	let decoder = decoder();
+
+	for spec in [LEGACY_SPEC, CURRENT_SPEC] {
+		// A plain entry, with a value:
+		let storage = decoder.decode_storage(spec, &storage_key("System", "Number"), Some(&1234u32.encode())).unwrap();
+		assert_eq!(keys_of(&storage), ["pallet", "name", "keys", "value"], "spec {spec}");
+		assert_eq!(storage["pallet"], "System");
+		assert_eq!(storage["name"], "Number");
+		assert_eq!(storage["keys"], Value::Array(Vec::new()));
+		assert!(!storage["value"].is_null());
+
+		// A map entry, without a value:
+		let account = [1u8; 32];
+		let key = [storage_key("System", "Account"), blake2_128(&account).to_vec(), account.to_vec()].concat();
+		let storage = decoder.decode_storage(spec, &key, None).unwrap();
+		assert_eq!(keys_of(&storage), ["pallet", "name", "keys", "value"], "spec {spec}");
+		assert_eq!(storage["name"], "Account");
+		assert!(storage["value"].is_null());
+
+		let keys = storage["keys"].as_array().expect("an array of keys");
+		assert_eq!(keys.len(), 1);
+		assert_eq!(keys_of(&keys[0]), ["hasher", "bytes", "value"], "spec {spec}");
+		assert_eq!(keys[0]["hasher"], "Blake2_128Concat");
+	}
+}
+
+#[test]
+fn storage_values_must_be_fully_consumed() {
+	let decoder = decoder();
+
+	for spec in [LEGACY_SPEC, CURRENT_SPEC] {
+		let value = [1234u32.encode(), vec![0]].concat();
+		let res = decoder.decode_storage(spec, &storage_key("System", "Number"), Some(&value));
+		assert!(res.is_err(), "spec {spec}");
+	}
+}
+
+#[test]
+fn unknown_spec_versions() {
+	let decoder = decoder();
+
+	assert!(matches!(decoder.decode_events(1, &[0]), Err(Error::SpecVersionNotFound(1))));
+	assert!(matches!(
+		decoder.decode_storage(1, &storage_key("System", "Number"), None),
+		Err(Error::SpecVersionNotFound(1))
+	));
+}
+
+#[test]
+fn diff_legacy_and_current_versions() {
+	let mut decoder = decoder();
+	decoder.register_version(V13_SPEC, &runtime_v13()).unwrap();
+
+	let diff = decoder.diff_versions(V13_SPEC, CURRENT_SPEC).unwrap();
+	assert!(diff.pallets_removed.iter().any(|p| p == "Society"));
+	assert!(diff.pallets_renamed.iter().any(|r| r.from == "ParasDmp" && r.to == "Dmp"));
+
+	// Types can't be compared across eras, but everything else still can be:
+	let balances = diff.pallets_changed.iter().find(|p| p.name == "Balances").expect("Balances changed");
+	assert_eq!(balances.index.as_ref().map(|i| (i.from, i.to)), Some((4, 5)));
+	assert!(balances.calls.field_changes.is_empty() && balances.events.field_changes.is_empty());
+	assert_eq!(balances.storage.added, ["Reserves"]);
+	assert!(balances.storage.changed.is_empty());
+
+	// Rather than being assumed to be unchanged, anything whose types couldn't be compared is reported:
+	assert!(balances.calls.not_comparable.iter().any(|c| c == "transfer"));
+	assert!(balances.events.not_comparable.iter().any(|e| e == "Transfer"));
+	assert!(balances.storage.not_comparable.iter().any(|s| s == "Account"));
+
+	let system = diff.pallets_changed.iter().find(|p| p.name == "System").expect("System changed");
+	assert!(system.storage.changed.is_empty() && system.events.field_changes.is_empty());
+	let remark = system.calls.field_changes.iter().find(|c| c.name == "remark").expect("remark changed");
+	assert_eq!(remark.from[0].name.as_deref(), Some("_remark"));
+	assert_eq!(remark.to[0].name.as_deref(), Some("remark"));
+	assert!(remark.from[0].structure.is_none() && remark.to[0].structure.is_some());
+	assert!(!system.calls.not_comparable.iter().any(|c| c == "remark"));
+
+	// Legacy versions are still compared against each other by type name:
+	assert!(decoder.diff_versions(V13_SPEC, V13_SPEC).unwrap().is_empty());
+	let diff = decoder.diff_versions(LEGACY_SPEC, V13_SPEC).unwrap();
+	assert!(diff
+		.pallets_changed
+		.iter()
+		.all(|p| p.calls.not_comparable.is_empty() && p.storage.not_comparable.is_empty()));
+	// ..with `<T as Trait>::Call` being the same type as `<T as Config>::Call`:
+	let utility = diff.pallets_changed.iter().find(|p| p.name == "Utility");
+	assert!(utility.map_or(true, |u| !u.calls.field_changes.iter().any(|c| c.name == "batch")));
+}
@@ -1,3 +1,6 @@
+mod events;
+mod facade;
 mod metadata;
 mod runtime_metadata;
 mod storage;
+mod v15_metadata;
@@ -71,3 +71,14 @@ pub fn runtime_v12_block_4643974() -> Vec<u8> {
 	f.read_to_end(&mut buffer).expect("Reading file failed");
 	buffer
 }
+
+/// Kusama runtime metadata for metadata version 13, as found in the `frame-metadata` test data.
+///
+/// # Panics
+/// Panics on std::io::Error
+pub fn runtime_v13() -> Vec<u8> {
+	let mut f = File::open("./data/metadata_v13.bin").expect("Opening file failed");
+	let mut buffer = Vec::new();
+	f.read_to_end(&mut buffer).expect("Reading file failed");
+	buffer
+}
@@ -0,0 +1,72 @@
+use desub_current::{decoder, Metadata, Value};
+use frame_support::derive_impl;
+use parity_scale_codec::Encode;
+
+// A minimal runtime, so that we can check real V15 metadata generated by FRAME.
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+frame_support::construct_runtime!(
+	pub struct Runtime {
+		System: frame_system,
+		Balances: pallet_balances,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
+impl frame_system::Config for Runtime {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig as pallet_balances::DefaultConfig)]
+impl pallet_balances::Config for Runtime {
+	type DustRemoval = ();
+	type ExistentialDeposit = frame_support::traits::ConstU64<500>;
+	type AccountStore = System;
+	type RuntimeHoldReason = ();
+}
+
+/// The SCALE encoded output of the `Metadata_metadata_at_version` runtime API.
+fn metadata_at_version(version: u32) -> Vec<u8> {
+	Runtime::metadata_at_version(version).encode()
+}
+
+#[test]
+fn decode_real_v15_metadata() {
+	let meta = Metadata::from_metadata_at_version(&metadata_at_version(15)).unwrap().expect("V15 is supported");
+	assert_eq!(meta.version(), 15);
+
+	assert!(meta.outer_enums().is_some());
+	assert!(meta.pallet("System").is_some());
+	let balances = meta.pallet("Balances").expect("Balances pallet exists");
+	assert!(balances.call("transfer_allow_death").is_some());
+
+	let existential_deposit = balances.constant("ExistentialDeposit").expect("constant exists");
+	assert_eq!(existential_deposit.value(&meta).unwrap().remove_context(), Value::u128(500));
+
+	// Balances.transfer_allow_death(dest: 2, value: 1000), unsigned:
+	let call = RuntimeCall::Balances(pallet_balances::Call::transfer_allow_death { dest: 2, value: 1000 });
+	let bytes = [vec![0x04], call.encode()].concat();
+	let ext = decoder::decode_unwrapped_extrinsic(&meta, &mut &*bytes).unwrap();
+	assert_eq!(ext.call_data.pallet_name, "Balances");
+	assert_eq!(ext.call_data.ty.name, "transfer_allow_death");
+}
+
+#[test]
+fn v14_and_v15_metadata_agree() {
+	let v14 = Metadata::from_metadata_at_version(&metadata_at_version(14)).unwrap().expect("V14 is supported");
+	let v15 = Metadata::from_metadata_at_version(&metadata_at_version(15)).unwrap().expect("V15 is supported");
+
+	assert_eq!(v14.version(), 14);
+	assert_eq!(v14.pallets().map(|p| p.name().to_owned()).collect::<Vec<_>>(), ["System", "Balances"]);
+	assert_eq!(
+		v14.pallets().map(|p| p.name().to_owned()).collect::<Vec<_>>(),
+		v15.pallets().map(|p| p.name().to_owned()).collect::<Vec<_>>()
+	);
+	assert_eq!(v14.constants().count(), v15.constants().count());
+}
+
+#[test]
+fn unsupported_metadata_versions() {
+	assert!(Metadata::from_metadata_at_version(&metadata_at_version(1)).unwrap().is_none());
+}